@@ -0,0 +1,262 @@
+use bevy::ecs::component::Mutable;
+use bevy::prelude::*;
+use bevy_common_assets::json::JsonAssetPlugin;
+use rand::Rng;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins(JsonAssetPlugin::<AnimationClips>::new(&["clips.json"]))
+        .add_systems(Update, (animate::<Sprite>, animate::<ImageNode>));
+}
+
+/// Named clips for one sprite sheet, loaded from a `*.clips.json` file in `assets/data`.
+#[derive(Deserialize, Asset, TypePath, Debug, Clone, Deref)]
+pub struct AnimationClips(HashMap<String, AnimationClip>);
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AnimationClip {
+    /// First and last atlas index, both included
+    pub frames: [usize; 2],
+    /// Seconds each frame shows for
+    pub frame_time: f32,
+    /// Frames that show for longer or shorter than `frame_time`, by atlas index
+    #[serde(default)]
+    pub frame_times: HashMap<usize, f32>,
+    #[serde(default)]
+    pub mode: PlayMode,
+    /// Clips that may play once in place of this one each time it comes round again, so idle
+    /// loops don't look mechanical
+    #[serde(default)]
+    pub variations: Vec<Variation>,
+}
+
+impl AnimationClip {
+    fn first(&self) -> usize {
+        self.frames[0]
+    }
+
+    fn last(&self) -> usize {
+        self.frames[1]
+    }
+
+    fn frame_time(&self, frame: usize) -> f32 {
+        self.frame_times
+            .get(&frame)
+            .copied()
+            .unwrap_or(self.frame_time)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayMode {
+    #[default]
+    Loop,
+    /// Plays forwards then backwards, a cycle ends back on the first frame
+    PingPong,
+    /// Stops on the last frame
+    Once,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Variation {
+    pub clip: String,
+    /// From 0.0 to 1.0, checked in order each cycle
+    pub chance: f32,
+}
+
+/// Plays clips on the texture atlas of a `Sprite` or `ImageNode` on the same entity.
+#[derive(Component, Debug, Clone)]
+pub struct Animator {
+    pub clips: Handle<AnimationClips>,
+    /// The clip asked for
+    clip: String,
+    /// The clip on screen, either `clip` or one of its variations
+    playing: String,
+    frame: usize,
+    forward: bool,
+    elapsed: f32,
+    restart: bool,
+    finished: bool,
+}
+
+impl Animator {
+    pub fn new(clips: Handle<AnimationClips>, clip: impl Into<String>) -> Self {
+        let clip = clip.into();
+        Self {
+            clips,
+            playing: clip.clone(),
+            clip,
+            frame: 0,
+            forward: true,
+            elapsed: 0.0,
+            restart: true,
+            finished: false,
+        }
+    }
+
+    /// Switches to `clip` from its first frame. Asking for the clip that is already playing
+    /// carries on where it is.
+    pub fn play(&mut self, clip: &str) {
+        if self.clip == clip {
+            return;
+        }
+
+        self.clip = clip.to_string();
+        self.playing = self.clip.clone();
+        self.restart = true;
+        self.finished = false;
+    }
+
+    /// Whether a `Once` clip has reached the end of its last frame
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    fn start(&mut self, clip: &AnimationClip) {
+        self.frame = clip.first();
+        self.forward = true;
+        self.elapsed = 0.0;
+        self.restart = false;
+    }
+
+    /// Picks what plays next once a cycle ends. Variations go back to the clip asked for. The
+    /// animation stops where it is if the clip has gone missing, say after a hot reload.
+    fn next_cycle(&mut self, clips: &AnimationClips) {
+        let Some(clip) = clips.get(&self.clip) else {
+            warn_once!("Missing animation clip {}", self.clip);
+            self.finished = true;
+            return;
+        };
+
+        if self.playing == self.clip {
+            let mut rng = rand::rng();
+            if let Some(variation) = clip
+                .variations
+                .iter()
+                .find(|variation| rng.random::<f32>() < variation.chance)
+                .filter(|variation| clips.contains_key(&variation.clip))
+            {
+                self.playing = variation.clip.clone();
+            }
+        } else {
+            self.playing = self.clip.clone();
+        }
+
+        let elapsed = self.elapsed;
+        self.start(&clips[&self.playing]);
+        self.elapsed = elapsed;
+    }
+
+    fn advance(&mut self, clips: &AnimationClips) {
+        let clip = &clips[&self.playing];
+        match clip.mode {
+            PlayMode::Loop if self.frame >= clip.last() => self.next_cycle(clips),
+            PlayMode::Loop => self.frame += 1,
+            PlayMode::PingPong if clip.first() == clip.last() => self.next_cycle(clips),
+            PlayMode::PingPong if self.forward && self.frame >= clip.last() => {
+                self.forward = false;
+                self.frame -= 1;
+            }
+            PlayMode::PingPong if self.forward => self.frame += 1,
+            PlayMode::PingPong if self.frame <= clip.first() => self.next_cycle(clips),
+            PlayMode::PingPong => self.frame -= 1,
+            PlayMode::Once if self.frame < clip.last() => self.frame += 1,
+            PlayMode::Once if self.playing != self.clip => self.next_cycle(clips),
+            PlayMode::Once => self.finished = true,
+        }
+    }
+}
+
+/// Anything drawn from a texture atlas that an `Animator` can drive.
+pub trait AtlasImage {
+    fn atlas_mut(&mut self) -> Option<&mut TextureAtlas>;
+}
+
+impl AtlasImage for Sprite {
+    fn atlas_mut(&mut self) -> Option<&mut TextureAtlas> {
+        self.texture_atlas.as_mut()
+    }
+}
+
+impl AtlasImage for ImageNode {
+    fn atlas_mut(&mut self) -> Option<&mut TextureAtlas> {
+        self.texture_atlas.as_mut()
+    }
+}
+
+pub fn animate<T: Component<Mutability = Mutable> + AtlasImage>(
+    time: Res<Time>,
+    clip_assets: Res<Assets<AnimationClips>>,
+    mut animated: Query<(&mut Animator, &mut T)>,
+) {
+    for (mut animator, mut image) in animated.iter_mut() {
+        let Some(clips) = clip_assets.get(&animator.clips) else {
+            continue;
+        };
+        let Some(clip) = clips.get(&animator.playing) else {
+            warn_once!("Missing animation clip {}", animator.playing);
+            continue;
+        };
+
+        if animator.restart {
+            animator.start(clip);
+        } else if !animator.finished {
+            animator.elapsed += time.delta_secs();
+            // A long frame can skip several short ones
+            while !animator.finished {
+                let Some(clip) = clips.get(&animator.playing) else {
+                    break;
+                };
+                let frame_time = clip.frame_time(animator.frame);
+                if frame_time <= 0.0 || animator.elapsed < frame_time {
+                    break;
+                }
+                animator.elapsed -= frame_time;
+                animator.advance(clips);
+            }
+        }
+
+        // Only flag the image as changed when the frame actually moves on
+        let frame = animator.frame;
+        let moved = match image.bypass_change_detection().atlas_mut() {
+            Some(atlas) if atlas.index != frame => {
+                atlas.index = frame;
+                true
+            }
+            _ => false,
+        };
+        if moved {
+            image.set_changed();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clip(frames: [usize; 2]) -> AnimationClip {
+        AnimationClip {
+            frames,
+            frame_time: 0.1,
+            frame_times: HashMap::new(),
+            mode: PlayMode::Loop,
+            variations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn missing_clip_stops_the_animation() {
+        // Playing a variation of "idle" when a reload drops "idle" itself
+        let mut animator = Animator::new(Handle::default(), "idle");
+        let reloaded = AnimationClips(HashMap::from([("walk".to_string(), clip([4, 7]))]));
+        animator.playing = "walk".into();
+        animator.frame = 7;
+        animator.advance(&reloaded);
+
+        assert!(animator.finished());
+        assert_eq!(animator.frame, 7);
+    }
+}
@@ -4,9 +4,11 @@ use bevy_aspect_ratio_mask::{AspectRatioPlugin, Resolution};
 pub const RESOLUTION_WIDTH: f32 = 600.0;
 pub const RESOLUTION_HEIGHT: f32 = 480.0;
 pub const AFTER_LOADING_STATE: AppState = AppState::Menu;
-pub const RUNNING_SPEED: f32 = 250.0;
 
-use crate::{assets, game, menu, util};
+use crate::{
+    animation, assets, biome, difficulty, feedback, game, input, level, menu, mode, pause, players,
+    ruleset, spatial, touch, util,
+};
 
 const TITLE: &str = "The Dino Game";
 
@@ -21,6 +23,8 @@ pub enum AppState {
     GameOver,
     HighScores,
     Credits,
+    /// Passes straight through to `Game`, so a run can be restarted from inside a run
+    Restarting,
 }
 
 pub fn start() {
@@ -59,10 +63,20 @@ pub fn start() {
             menu::Menu,
             assets::plugin,
             game::plugin,
+            input::plugin,
+            biome::plugin,
+            difficulty::plugin,
+            ruleset::plugin,
+            level::plugin,
+            mode::plugin,
+            pause::plugin,
+            spatial::plugin,
+            touch::plugin,
             util::plugin,
             // #[cfg(feature = "dev")]
             // crate::dev_tools::plugin,
         ))
+        .add_plugins((animation::plugin, feedback::plugin, players::plugin))
         .run();
 }
 
@@ -1,3 +1,7 @@
+use crate::animation::AnimationClips;
+use crate::biome::BiomeSet;
+use crate::difficulty::DifficultyCurve;
+use crate::ruleset::Ruleset;
 use bevy::ecs::resource::Resource;
 use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
@@ -68,6 +72,67 @@ pub struct ImageAssets {
 
     #[asset(texture_atlas_layout(tile_size_x = 512, tile_size_y = 320, columns = 7, rows = 2))]
     pub circle_transition_layout: Handle<TextureAtlasLayout>,
+
+    #[asset(path = "clouds-background.png")]
+    #[asset(image(sampler(filter = nearest)))]
+    pub clouds_background: Handle<Image>,
+
+    #[asset(path = "cloud-platform.png")]
+    #[asset(image(sampler(filter = nearest)))]
+    pub cloud_platform: Handle<Image>,
+
+    #[asset(path = "beanstalk.png")]
+    #[asset(image(sampler(filter = nearest)))]
+    pub beanstalk: Handle<Image>,
+
+    #[asset(path = "night-background.png")]
+    #[asset(image(sampler(filter = nearest)))]
+    pub night_background: Handle<Image>,
+
+    #[asset(path = "night-platform.png")]
+    #[asset(image(sampler(filter = nearest)))]
+    pub night_platform: Handle<Image>,
+
+    #[asset(path = "night-tree.png")]
+    #[asset(image(sampler(filter = nearest)))]
+    pub night_tree: Handle<Image>,
+}
+
+impl ImageAssets {
+    /// Biome art by the file name `data/world.biomes.json` uses for it
+    pub fn biome_image(&self, path: &str) -> Option<&Handle<Image>> {
+        match path {
+            "forest-tilemap.png" => Some(&self.forest_tilemap),
+            "leaves.png" => Some(&self.leaves),
+            "tree.png" => Some(&self.tree),
+            "clouds-background.png" => Some(&self.clouds_background),
+            "cloud-platform.png" => Some(&self.cloud_platform),
+            "beanstalk.png" => Some(&self.beanstalk),
+            "night-background.png" => Some(&self.night_background),
+            "night-platform.png" => Some(&self.night_platform),
+            "night-tree.png" => Some(&self.night_tree),
+            _ => None,
+        }
+    }
+}
+
+#[derive(AssetCollection, Resource)]
+
+pub struct DataAssets {
+    #[asset(path = "data/world.biomes.json")]
+    pub biomes: Handle<BiomeSet>,
+
+    #[asset(path = "data/world.difficulty.json")]
+    pub difficulty: Handle<DifficultyCurve>,
+
+    #[asset(path = "data/game.ruleset.json")]
+    pub ruleset: Handle<Ruleset>,
+
+    #[asset(path = "data/dino.clips.json")]
+    pub dino_clips: Handle<AnimationClips>,
+
+    #[asset(path = "data/circle-transition.clips.json")]
+    pub transition_clips: Handle<AnimationClips>,
 }
 
 #[derive(AssetCollection, Resource)]
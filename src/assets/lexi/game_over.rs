@@ -18,6 +18,10 @@ fn preload(mut commands: Commands, asset_server: Res<AssetServer>) {
             "lexi/game-over/win.json",
             "lexi/game-over/lose.json",
             "lexi/game-over/submit.json",
+            "lexi/game-over/seed.json",
+            "lexi/game-over/replay.json",
+            "lexi/game-over/winner.json",
+            "lexi/game-over/coop_win.json",
         ],
     ));
 }
@@ -18,6 +18,9 @@ fn preload(mut commands: Commands, asset_server: Res<AssetServer>) {
             "lexi/menu/main.json",
             "lexi/menu/language.json",
             "lexi/menu/howto.json",
+            "lexi/menu/seed.json",
+            "lexi/menu/levels.json",
+            "lexi/menu/modes.json",
             // ...more menus here,
         ],
     ));
@@ -28,11 +31,10 @@ pub struct MenuData {
     pub id: String,
     pub lex: Lexicon,
     pub choices: Option<Vec<Choice>>,
+    /// Adds a text field under the header, e.g. "seed"
+    pub input: Option<String>,
 }
 
-#[derive(serde::Deserialize, Asset, TypePath, Debug, Default, Clone)]
-pub struct Display {}
-
 #[derive(serde::Deserialize, Asset, TypePath, Debug, Default, Clone)]
 pub struct Choice {
     pub id: String,
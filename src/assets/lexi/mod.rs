@@ -6,6 +6,7 @@ use crate::app::AppState;
 // pub mod game;
 pub mod game_over;
 pub mod menu;
+pub mod pause;
 // pub mod splash;
 
 pub(super) fn plugin(app: &mut App) {
@@ -13,6 +14,7 @@ pub(super) fn plugin(app: &mut App) {
         menu::plugin,
         // game::plugin,
         game_over::plugin,
+        pause::plugin,
         // splash::plugin,
     ));
     app.add_systems(Update, preload.run_if(in_state(AppState::Preload)));
@@ -29,7 +31,7 @@ impl<A: Asset + TypePath> LexiCollection<A> {
     {
         let handles = files
             .into_iter()
-            .map(|file| asset_server.load(&file.into()))
+            .map(|file| asset_server.load(file.into()))
             .collect::<Vec<_>>();
 
         Self(handles)
@@ -42,7 +44,7 @@ pub struct Lexicon {
 }
 
 impl Lexicon {
-    pub fn from_language(&self, language: impl Into<String>) -> String {
+    pub fn in_language(&self, language: impl Into<String>) -> String {
         self.translations
             .get(&language.into())
             .cloned()
@@ -0,0 +1,46 @@
+use crate::{
+    app::AppState,
+    assets::lexi::{LexiCollection, Lexicon},
+};
+use bevy::prelude::*;
+use bevy_common_assets::json::JsonAssetPlugin;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins(JsonAssetPlugin::<PauseLex>::new(&[".json"]));
+    app.add_systems(OnEnter(AppState::Preload), preload);
+}
+
+fn preload(mut commands: Commands, asset_server: Res<AssetServer>) {
+    info!("Loading Pause Options");
+    commands.insert_resource(LexiCollection::<PauseLex>::new(
+        &asset_server,
+        vec![
+            "lexi/pause/paused.json",
+            "lexi/pause/resume.json",
+            "lexi/pause/restart.json",
+            "lexi/pause/settings.json",
+            "lexi/pause/quit.json",
+            "lexi/pause/music.json",
+            "lexi/pause/sound.json",
+            "lexi/pause/back.json",
+            "lexi/pause/on.json",
+            "lexi/pause/off.json",
+            "lexi/pause/continue.json",
+            "lexi/pause/controls.json",
+            "lexi/pause/action_jump.json",
+            "lexi/pause/action_left.json",
+            "lexi/pause/action_right.json",
+            "lexi/pause/action_attack.json",
+            "lexi/pause/action_pause.json",
+            "lexi/pause/reset_controls.json",
+            "lexi/pause/waiting.json",
+            "lexi/pause/reduce_motion.json",
+        ],
+    ));
+}
+
+#[derive(serde::Deserialize, Asset, TypePath, Debug, Default, Clone)]
+pub struct PauseLex {
+    pub id: String,
+    pub lex: Lexicon,
+}
@@ -13,6 +13,7 @@ pub(super) fn plugin(app: &mut App) {
         LoadingState::new(AppState::Loading)
             .continue_to_state(AFTER_LOADING_STATE)
             .load_collection::<custom::ImageAssets>()
-            .load_collection::<custom::SoundAssets>(),
+            .load_collection::<custom::SoundAssets>()
+            .load_collection::<custom::DataAssets>(),
     );
 }
@@ -0,0 +1,183 @@
+use crate::app::AppState;
+use crate::assets::custom::{DataAssets, ImageAssets};
+use crate::camera::GameCamera;
+use crate::worldgen::{self, TileRules};
+use bevy::prelude::*;
+use bevy_common_assets::json::JsonAssetPlugin;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins(JsonAssetPlugin::<BiomeSet>::new(&["biomes.json"]))
+        .insert_resource(CurrentBiome::default())
+        .add_systems(OnEnter(AppState::Game), reset_current_biome)
+        .add_systems(
+            Update,
+            update_biome_background.run_if(in_state(AppState::Game)),
+        );
+}
+
+/// Altitude bands of the climb, loaded from `data/world.biomes.json`.
+#[derive(serde::Deserialize, Asset, TypePath, Debug, Clone)]
+pub struct BiomeSet {
+    pub bands: Vec<Biome>,
+}
+
+impl BiomeSet {
+    /// The band tile row `j` belongs to. Rows below the first band use the lowest one.
+    pub fn band(&self, j: i32) -> Option<&Biome> {
+        self.bands
+            .iter()
+            .filter(|band| band.from_tile <= j)
+            .max_by_key(|band| band.from_tile)
+            .or_else(|| self.bands.iter().min_by_key(|band| band.from_tile))
+    }
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct Biome {
+    pub name: String,
+    /// First tile row (`j`) of the band, it lasts until the next band starts
+    pub from_tile: i32,
+    pub background: String,
+    pub background_tint: [f32; 3],
+    pub platform: String,
+    pub platform_tint: [f32; 3],
+    pub tree: String,
+    pub tree_tint: [f32; 3],
+    pub tree_chance: f64,
+    pub apple_chance: f64,
+    pub clock_chance: f64,
+}
+
+impl Default for Biome {
+    /// The forest, used until the biome data is available
+    fn default() -> Self {
+        let rules = TileRules::default();
+        Self {
+            name: "forest".into(),
+            from_tile: 0,
+            background: "forest-tilemap.png".into(),
+            background_tint: [1.0, 1.0, 1.0],
+            platform: "leaves.png".into(),
+            platform_tint: [1.0, 1.0, 1.0],
+            tree: "tree.png".into(),
+            tree_tint: [1.0, 1.0, 1.0],
+            tree_chance: rules.tree_chance,
+            apple_chance: rules.apple_chance,
+            clock_chance: rules.clock_chance,
+        }
+    }
+}
+
+impl Biome {
+    pub fn rules(&self) -> TileRules {
+        TileRules {
+            tree_chance: self.tree_chance,
+            apple_chance: self.apple_chance,
+            clock_chance: self.clock_chance,
+            ..default()
+        }
+    }
+
+    pub fn background_sprite(&self, assets: &ImageAssets) -> Sprite {
+        let fallback = &assets.forest_tilemap;
+        tinted_sprite(assets, &self.background, fallback, self.background_tint)
+    }
+
+    pub fn platform_sprite(&self, assets: &ImageAssets) -> Sprite {
+        tinted_sprite(assets, &self.platform, &assets.leaves, self.platform_tint)
+    }
+
+    pub fn tree_sprite(&self, assets: &ImageAssets) -> Sprite {
+        tinted_sprite(assets, &self.tree, &assets.tree, self.tree_tint)
+    }
+}
+
+/// Sprites for the platforms and trees of one band.
+pub struct BiomeArt {
+    pub platform: Sprite,
+    pub tree: Sprite,
+}
+
+impl BiomeArt {
+    /// Art of the band tile row `j` belongs to, or the forest while the biome data isn't
+    /// available.
+    pub fn at(biomes: Option<&BiomeSet>, j: i32, assets: &ImageAssets) -> Self {
+        match biomes.and_then(|set| set.band(j)) {
+            Some(biome) => Self {
+                platform: biome.platform_sprite(assets),
+                tree: biome.tree_sprite(assets),
+            },
+            None => Self {
+                platform: Sprite::from_image(assets.leaves.clone()),
+                tree: Sprite::from_image(assets.tree.clone()),
+            },
+        }
+    }
+}
+
+/// Band art comes from `ImageAssets` so it's ready before the band is first reached. Art that
+/// isn't part of the collection falls back to the forest's.
+fn tinted_sprite(
+    assets: &ImageAssets,
+    path: &str,
+    fallback: &Handle<Image>,
+    tint: [f32; 3],
+) -> Sprite {
+    let image = assets.biome_image(path).unwrap_or_else(|| {
+        warn!(
+            path,
+            "Biome art is not part of ImageAssets, using the forest's"
+        );
+        fallback
+    });
+    Sprite {
+        image: image.clone(),
+        color: Color::srgb(tint[0], tint[1], tint[2]),
+        ..default()
+    }
+}
+
+/// Marks the sprites of the parallax background so they can be swapped between bands.
+#[derive(Component, Clone)]
+pub struct BiomeBackground;
+
+/// Name of the band the camera is currently in.
+#[derive(Resource, Default)]
+pub struct CurrentBiome(pub Option<String>);
+
+fn reset_current_biome(mut current: ResMut<CurrentBiome>) {
+    current.0 = None;
+}
+
+fn update_biome_background(
+    assets: Res<ImageAssets>,
+    data: Res<DataAssets>,
+    biome_sets: Res<Assets<BiomeSet>>,
+    mut current: ResMut<CurrentBiome>,
+    camera_query: Query<&Transform, With<GameCamera>>,
+    mut background_query: Query<&mut Sprite, With<BiomeBackground>>,
+) {
+    let Ok(camera_transform) = camera_query.single() else {
+        return;
+    };
+    let Some(biomes) = biome_sets.get(&data.biomes) else {
+        return;
+    };
+
+    let (_, j) = worldgen::tile_at(camera_transform.translation.truncate());
+    let Some(band) = biomes.band(j) else {
+        return;
+    };
+
+    if current.0.as_deref() == Some(band.name.as_str()) {
+        return;
+    }
+
+    info!(biome = band.name, "Entering biome");
+    current.0 = Some(band.name.clone());
+    let background = band.background_sprite(&assets);
+    for mut sprite in background_query.iter_mut() {
+        sprite.image = background.image.clone();
+        sprite.color = background.color;
+    }
+}
@@ -1,41 +1,54 @@
-use crate::app::{RESOLUTION_HEIGHT, RESOLUTION_WIDTH};
-use crate::game::Player;
+use crate::app::{AppState, RESOLUTION_HEIGHT, RESOLUTION_WIDTH};
+use crate::assets::custom::ImageAssets;
+use crate::biome::BiomeBackground;
+use crate::feedback::CameraShake;
+use crate::game::{Dino, Player};
 use bevy::prelude::*;
 use bevy::render::camera::ScalingMode;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
 
 #[derive(Component, Default)]
 pub struct GameCamera {
     pub selected_game_level: GameLevelDimensions,
 }
 
-#[derive(Component, Default)]
+#[derive(Component, Default, Clone, Debug, serde::Deserialize)]
 pub struct GameLevelDimensions {
-    left: f32,
-    right: f32,
-    top: f32,
-    bottom: f32,
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+}
+
+impl GameLevelDimensions {
+    /// Bounds of the endless procedural climb
+    pub fn endless() -> Self {
+        Self {
+            left: -1000000.,   // Camera views -180 pixels left
+            top: 1000000.,     // Camera views 90 pixels up (top)
+            right: 1000000.,   // Camera views 1600 + 180 pixels right
+            bottom: -1000000., // Camera views 90 pixels down (bottom)
+        }
+    }
 }
 
 pub fn game_camera(
     mut commands: Commands,
     mut camera_query: Query<&mut Transform, With<GameCamera>>,
 ) {
-    if let Ok(_) = camera_query.single_mut() {
+    if camera_query.single_mut().is_ok() {
         return;
     }
 
     commands
         .spawn((
             GameCamera {
-                selected_game_level: GameLevelDimensions {
-                    left: -1000000.,   // Camera views -180 pixels left
-                    top: 1000000.,     // Camera views 90 pixels up (top)
-                    right: 1000000.,   // Camera views 1600 + 180 pixels right
-                    bottom: -1000000., // Camera views 90 pixels down (bottom)
-                },
-                ..default()
+                selected_game_level: GameLevelDimensions::endless(),
             },
-            Camera2d::default(),
+            CameraFollow::default(),
+            CameraShake::default(),
+            Camera2d,
             // Camera {
             //     hdr: true, // 1. HDR is required for bloom
             //     clear_color: ClearColorConfig::Custom(Color::BLACK),
@@ -71,140 +84,287 @@ pub fn game_camera(
         .insert(Transform::from_xyz(0., 0., 0.));
 }
 
+/// How the game camera follows the dino. The camera aims at the dino plus `offset`, shifted
+/// toward where it is heading, and only moves once that aim leaves the dead zone. With two
+/// players it follows the point halfway between them.
+#[derive(Component, Clone, Debug)]
+pub struct CameraFollow {
+    /// Where the camera rests relative to the dino, above it so there is more to see overhead
+    pub offset: Vec2,
+    /// Half the size of the box the aim can wander in before the camera follows
+    pub dead_zone: Vec2,
+    /// Seconds of the dino's velocity to look ahead by
+    pub look_ahead: Vec2,
+    /// Furthest the look-ahead can shift the aim
+    pub max_look_ahead: Vec2,
+    /// How quickly the look-ahead catches up with a change of direction, per second
+    pub look_ahead_rate: f32,
+    pub spring_x: Spring,
+    pub spring_y: Spring,
+    focus: Option<Vec2>,
+    look_ahead_shift: Vec2,
+    velocity: Vec2,
+}
+
+impl Default for CameraFollow {
+    fn default() -> Self {
+        Self {
+            offset: Vec2::new(0.0, 75.0),
+            dead_zone: Vec2::new(40.0, 30.0),
+            look_ahead: Vec2::new(0.3, 0.25),
+            max_look_ahead: Vec2::new(120.0, 200.0),
+            look_ahead_rate: 3.0,
+            spring_x: Spring::critical(30.0),
+            spring_y: Spring::critical(40.0),
+            focus: None,
+            look_ahead_shift: Vec2::ZERO,
+            velocity: Vec2::ZERO,
+        }
+    }
+}
+
+/// A spring on one axis with a mass of 1.0.
+#[derive(Clone, Copy, Debug)]
+pub struct Spring {
+    pub stiffness: f32,
+    pub damping: f32,
+}
+
+impl Spring {
+    /// Longest step the spring is integrated over. Slow frames take several steps, so a low
+    /// frame rate can't make the camera overshoot or blow up.
+    const MAX_STEP: f32 = 1.0 / 120.0;
+
+    /// Settles as fast as it can without overshooting
+    pub fn critical(stiffness: f32) -> Self {
+        Self {
+            stiffness,
+            damping: 2.0 * stiffness.sqrt(),
+        }
+    }
+
+    /// Moves `position` and `velocity` toward `target` over `delta` seconds
+    pub fn step(&self, position: &mut f32, velocity: &mut f32, target: f32, delta: f32) {
+        let steps = (delta / Self::MAX_STEP).ceil().max(1.0);
+        let h = delta / steps;
+        for _ in 0..steps as u32 {
+            let acceleration = -self.stiffness * (*position - target) - self.damping * *velocity;
+            *velocity += acceleration * h;
+            *position += *velocity * h;
+        }
+    }
+}
+
 pub fn camera_tracking_system(
     time: Res<Time>,
-    mut player_query: Query<&mut Transform, With<Player>>,
-    mut camera_query: Query<(&mut GameCamera, &mut Transform), Without<Player>>,
+    player_query: Query<(&Transform, &Dino), With<Player>>,
+    mut camera_query: Query<(&GameCamera, &mut CameraFollow, &mut Transform), Without<Player>>,
 ) {
-    // TODO track two players that have a diff < screen height else game over
-
-    let camera_above_center_const = 75.0;
-    // let player_transform = match {
-    //     Ok(t) => t,
-    //     Err(_) => return,
-    // };
-    let Ok(mut player_transform) = player_query.single_mut() else {
+    // Players drifting too far apart is handled in `players::keep_players_together`
+    let count = player_query.iter().len();
+    if count == 0 {
         return;
-    };
-
-    let player_average_position = &player_transform.translation;
+    }
 
-    let (game_camera, mut camera_transform) = match camera_query.single_mut() {
-        Ok(q) => q,
-        Err(_) => return,
+    let Ok((game_camera, mut follow, mut camera_transform)) = camera_query.single_mut() else {
+        return;
     };
+    let follow = &mut *follow;
+
+    let delta = time.delta_secs();
+    let (position_sum, velocity_sum) = player_query.iter().fold(
+        (Vec2::ZERO, Vec2::ZERO),
+        |(position, velocity), (transform, dino)| {
+            (
+                position + transform.translation.truncate(),
+                velocity + dino.velocity,
+            )
+        },
+    );
+    let player_position = position_sum / count as f32;
+    let player_velocity = velocity_sum / count as f32;
 
     if camera_transform.translation.is_nan() {
         info!("Init camera");
-        camera_transform.translation.x = player_average_position.x;
-        camera_transform.translation.y = player_average_position.y + camera_above_center_const;
+        let resting = player_position + follow.offset;
+        camera_transform.translation.x = resting.x;
+        camera_transform.translation.y = resting.y;
+        follow.focus = Some(resting);
         return;
     }
 
-    let (max, min) = (
-        game_camera.selected_game_level.top,
-        game_camera.selected_game_level.bottom,
-    );
-    let m = 1.0_f32;
-    let k = 8.5_f32;
-    let b = 2.0 * (m * k).sqrt();
-    let camera_position = camera_transform.translation.y;
-    let player_position = player_average_position.y + camera_above_center_const;
-    let delta = camera_position - player_position;
-    if delta.abs() < 0.10 {
-        let final_position = player_average_position.y + camera_above_center_const;
-        if final_position < max && final_position > min {
-            camera_transform.translation.y = player_average_position.y + camera_above_center_const;
-        }
-    } else {
-        let mut v = delta / time.delta_secs();
-
-        let a = -b * v - k * delta;
-        v += a * time.delta_secs();
-        let d = v * time.delta_secs();
-        let final_position = player_position + d;
+    // Ease the look-ahead toward the dino's heading, so turning around doesn't whip the view
+    let heading =
+        (player_velocity * follow.look_ahead).clamp(-follow.max_look_ahead, follow.max_look_ahead);
+    let ease = 1.0 - (-follow.look_ahead_rate * delta).exp();
+    follow.look_ahead_shift = follow.look_ahead_shift.lerp(heading, ease);
 
-        // Add conditional here for max y
-        if final_position < max && final_position > min {
-            camera_transform.translation.y = final_position;
-        }
-    }
+    // Drag the focus along only as far as the aim has left the dead zone
+    let aim = player_position + follow.offset + follow.look_ahead_shift;
+    let focus = follow.focus.get_or_insert(aim);
+    *focus = focus.clamp(aim - follow.dead_zone, aim + follow.dead_zone);
+    let focus = *focus;
 
-    let (max, min) = (
-        game_camera.selected_game_level.right,
-        game_camera.selected_game_level.left,
+    let bounds = &game_camera.selected_game_level;
+    let mut camera_position = camera_transform.translation.truncate();
+    follow.spring_x.step(
+        &mut camera_position.x,
+        &mut follow.velocity.x,
+        focus.x,
+        delta,
+    );
+    follow.spring_y.step(
+        &mut camera_position.y,
+        &mut follow.velocity.y,
+        focus.y,
+        delta,
     );
-    let m = 1.0_f32;
-    let k = 8.5_f32;
-    let b = 2.0 * (m * k).sqrt();
-    let camera_position = camera_transform.translation.x;
-    let player_position = player_average_position.x;
-    let delta = camera_position - player_position;
-    if delta.abs() < 0.10 {
-        let final_position = player_average_position.x;
-        if final_position < max && final_position > min {
-            camera_transform.translation.x = player_average_position.x;
-        }
-    } else {
-        let mut v = delta / time.delta_secs();
 
-        let a = -b * v - k * delta;
-        v += a * time.delta_secs();
-        let d = v * time.delta_secs();
-        let final_position = player_position + d;
+    // Stop dead at the edges of the level instead of pushing into them
+    let bounded = camera_position
+        .max(Vec2::new(bounds.left, bounds.bottom))
+        .min(Vec2::new(bounds.right, bounds.top));
+    if bounded.x != camera_position.x {
+        follow.velocity.x = 0.0;
+    }
+    if bounded.y != camera_position.y {
+        follow.velocity.y = 0.0;
+    }
+    camera_transform.translation.x = bounded.x;
+    camera_transform.translation.y = bounded.y;
+}
+
+/// One depth of the endless background. The layer follows the camera by `coefficient` and
+/// keeps a small grid of tiles centred on whichever tile is under the camera, so however far
+/// the dino climbs there is always background to see.
+#[derive(Component, Clone, Debug)]
+pub struct ParallaxLayer {
+    /// Share of the camera's movement the layer follows, 1.0 sticks to the screen and 0.0 sits
+    /// still in the world
+    pub coefficient: Vec2,
+    pub tile_size: Vec2,
+}
 
-        // Add conditional here for max y
+impl ParallaxLayer {
+    /// Room around the screen the tiles also cover, so a camera shake never shows an edge
+    const MARGIN: f32 = 32.0;
 
-        if final_position < max && final_position > min {
-            camera_transform.translation.x = final_position;
-        }
+    /// Tiles needed either side of the centre one to always cover the screen
+    fn tile_radius(&self) -> IVec2 {
+        let half_screen = Vec2::new(RESOLUTION_WIDTH, RESOLUTION_HEIGHT) / 2.0 + Self::MARGIN;
+        (half_screen / self.tile_size).ceil().as_ivec2()
     }
+}
 
-    let max_x = game_camera.selected_game_level.right * 0.9;
-    let min_x = game_camera.selected_game_level.left * 0.9;
-    let max_y = game_camera.selected_game_level.top * 0.9;
-    let min_y = game_camera.selected_game_level.bottom * 0.9;
+/// Spawns a layer at depth `z`, with every tile made from `tile`.
+pub fn spawn_parallax_layer(
+    commands: &mut Commands,
+    layer: ParallaxLayer,
+    z: f32,
+    tile: impl Bundle + Clone,
+) {
+    let radius = layer.tile_radius();
+    let tile_size = layer.tile_size;
+    commands
+        .spawn((
+            StateScoped(AppState::Game),
+            layer,
+            Transform::from_xyz(0., 0., z),
+            Visibility::default(),
+        ))
+        .with_children(|parent| {
+            for x in -radius.x..=radius.x {
+                for y in -radius.y..=radius.y {
+                    let offset = IVec2::new(x, y).as_vec2() * tile_size;
+                    parent.spawn((tile.clone(), Transform::from_translation(offset.extend(0.))));
+                }
+            }
+        });
+}
 
-    if player_transform.translation.x > max_x {
-        player_transform.translation.x = min_x;
-        camera_transform.translation.x += 2. * min_x;
-    }
-    if player_transform.translation.y > max_y {
-        player_transform.translation.y = min_y;
-        camera_transform.translation.y += 2. * min_y;
-    }
-    if player_transform.translation.x < min_x {
-        player_transform.translation.x = max_x;
-        camera_transform.translation.x += 2. * max_x;
-    }
-    if player_transform.translation.y < min_y {
-        player_transform.translation.y = max_y;
-        camera_transform.translation.y += 2. * max_y;
-    }
+/// An image that fades from `bottom` to `top`, stretched over the tiles of layers that are just
+/// colour.
+pub fn vertical_gradient(images: &mut Assets<Image>, bottom: Color, top: Color) -> Handle<Image> {
+    const ROWS: u32 = 256;
+    // Image rows run from the top down
+    let data = (0..ROWS)
+        .flat_map(|row| {
+            let t = row as f32 / (ROWS - 1) as f32;
+            top.mix(&bottom, t).to_srgba().to_u8_array()
+        })
+        .collect();
+    images.add(Image::new(
+        Extent3d {
+            width: 1,
+            height: ROWS,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    ))
 }
 
-#[derive(Component)]
-pub struct Parallax {
-    pub x_coeff: f32,
-    pub y_coeff: f32,
-    pub x_offset: f32,
-    pub y_offset: f32,
-    pub x_tile: i32,
-    pub y_tile: i32,
+/// The forest, swapped for each biome, with a haze in front that thickens toward the top of
+/// the screen.
+pub fn spawn_parallax_background(
+    mut commands: Commands,
+    assets: Res<ImageAssets>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    spawn_parallax_layer(
+        &mut commands,
+        ParallaxLayer {
+            coefficient: Vec2::splat(0.6),
+            tile_size: Vec2::new(2400., 1920.),
+        },
+        -20.,
+        (
+            BiomeBackground,
+            Sprite {
+                image: assets.forest_tilemap.clone(),
+                ..default()
+            },
+        ),
+    );
+
+    let screen = Vec2::new(RESOLUTION_WIDTH, RESOLUTION_HEIGHT);
+    let haze = vertical_gradient(
+        &mut images,
+        Color::srgba(0.85, 0.92, 1.0, 0.0),
+        Color::srgba(0.85, 0.92, 1.0, 0.3),
+    );
+    spawn_parallax_layer(
+        &mut commands,
+        ParallaxLayer {
+            coefficient: Vec2::ONE,
+            tile_size: screen,
+        },
+        -15.,
+        Sprite {
+            image: haze,
+            custom_size: Some(screen),
+            ..default()
+        },
+    );
 }
 
 pub fn parallax_system(
     camera_query: Query<&Transform, With<GameCamera>>,
-    mut background_query: Query<(&mut Transform, &Parallax), Without<GameCamera>>,
+    mut layer_query: Query<(&mut Transform, &ParallaxLayer), Without<GameCamera>>,
 ) {
     let Ok(camera_transform) = camera_query.single() else {
         return;
     };
+    let camera_position = camera_transform.translation.truncate();
 
-    for (mut background_transform, parallax) in background_query.iter_mut() {
-        background_transform.translation.x = camera_transform.translation.x * parallax.x_coeff
-            + parallax.x_offset * (parallax.x_tile as f32);
-        background_transform.translation.y = camera_transform.translation.y * parallax.y_coeff
-            + parallax.y_offset * (parallax.y_tile as f32);
+    for (mut layer_transform, layer) in layer_query.iter_mut() {
+        let origin = camera_position * layer.coefficient;
+        // Recentre the grid on the tile under the camera, the tiles around it cover the rest
+        let tile = ((camera_position - origin) / layer.tile_size).round();
+        let position = origin + tile * layer.tile_size;
+        layer_transform.translation.x = position.x;
+        layer_transform.translation.y = position.y;
     }
 }
@@ -1,3 +1,6 @@
+// Left out of `app.rs` until it is needed for debugging
+#![allow(dead_code)]
+
 use crate::app::AppState;
 use crate::game::Apple;
 use crate::game::Dino;
@@ -6,6 +9,7 @@ use crate::game::GameStatus;
 use crate::game::Obstacle;
 use crate::game::SceneChange;
 use crate::game::TimeExtender;
+use crate::input::Action;
 // use bevy::dev_tools::states::log_transitions;
 use bevy::input::common_conditions::input_just_pressed;
 use bevy::prelude::*;
@@ -13,9 +17,9 @@ use bevy::prelude::*;
 pub(super) fn plugin(app: &mut App) {
     app
         // .add_systems(Update, log_transitions::<AppState>)
-        .add_systems(Update, restart)
-        .add_systems(Update, lose.run_if(input_just_pressed(KeyCode::KeyX)))
-        .add_systems(Update, win.run_if(input_just_pressed(KeyCode::KeyZ)))
+        .add_systems(Update, restart.run_if(input_just_pressed(Action::Restart)))
+        .add_systems(Update, lose.run_if(input_just_pressed(Action::DebugLose)))
+        .add_systems(Update, win.run_if(input_just_pressed(Action::DebugWin)))
         .add_systems(
             PostUpdate,
             draw_aabb_gizmos.run_if(in_state(AppState::Game)),
@@ -42,13 +46,8 @@ fn win(
     commands.send_event(SceneChange(AppState::GameOver));
 }
 
-fn restart(
-    mut loading_state: ResMut<NextState<AppState>>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-) {
-    if keyboard_input.just_pressed(KeyCode::F1) {
-        loading_state.set(AppState::Game);
-    }
+fn restart(mut loading_state: ResMut<NextState<AppState>>) {
+    loading_state.set(AppState::Restarting);
 }
 
 pub fn draw_aabb_gizmos(
@@ -0,0 +1,172 @@
+use crate::app::AppState;
+use crate::game::GameState;
+use crate::worldgen::TileRules;
+use bevy::prelude::*;
+use bevy::time::Stopwatch;
+use bevy_common_assets::json::JsonAssetPlugin;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins(JsonAssetPlugin::<DifficultyCurve>::new(&[
+        "difficulty.json",
+    ]))
+    .insert_resource(RunTime::default())
+    .add_systems(OnEnter(AppState::Game), reset_run_time)
+    .add_systems(
+        Update,
+        tick_run_time.run_if(in_state(AppState::Game).and(in_state(GameState::Running))),
+    );
+}
+
+/// How long the current run has been going. Unlike `GameTimer` clocks don't extend it.
+#[derive(Resource, Default)]
+pub struct RunTime(pub Stopwatch);
+
+fn reset_run_time(mut run_time: ResMut<RunTime>) {
+    run_time.0.reset();
+}
+
+fn tick_run_time(time: Res<Time>, mut run_time: ResMut<RunTime>) {
+    run_time.0.tick(time.delta());
+}
+
+/// How the world gets harder, loaded from `data/world.difficulty.json`. Climbing time only
+/// counts in whole `time_step`s, so a tile is the same for a seed, its altitude and the step it
+/// was generated in, however the climb went.
+#[derive(serde::Deserialize, Asset, TypePath, Debug, Clone)]
+pub struct DifficultyCurve {
+    /// Difficulty added by altitude, as `[height, difficulty]` points
+    pub height: Vec<Vec2>,
+    /// Difficulty added by the seconds spent climbing, as `[seconds, difficulty]` points
+    pub time: Vec<Vec2>,
+    /// Seconds of climbing that make up one step of the time curve
+    pub time_step: f32,
+    pub easiest: DifficultySettings,
+    pub hardest: DifficultySettings,
+}
+
+/// Generator settings at one end of the curve, everything in between is interpolated.
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct DifficultySettings {
+    pub platform_spacing: f32,
+    pub platform_half_width: f32,
+    pub max_elements: f32,
+    /// Multiplies the apple and clock chances of the biome
+    pub collectible_rate: f64,
+    /// Multiplies the tree chance of the biome
+    pub hazard_density: f64,
+}
+
+impl DifficultyCurve {
+    /// Which step of the time curve `elapsed_secs` falls in
+    pub fn time_bucket(&self, elapsed_secs: f32) -> u32 {
+        (elapsed_secs.max(0.0) / self.time_step.max(1.0)).floor() as u32
+    }
+
+    /// 0 is the easiest and 1 the hardest. Time is read at the start of its step.
+    pub fn difficulty(&self, height: f32, time_bucket: u32) -> f32 {
+        let seconds = time_bucket as f32 * self.time_step.max(1.0);
+        (sample(&self.height, height) + sample(&self.time, seconds)).clamp(0.0, 1.0)
+    }
+
+    pub fn apply(&self, rules: TileRules, difficulty: f32) -> TileRules {
+        let (easiest, hardest) = (&self.easiest, &self.hardest);
+        let t = difficulty as f64;
+        let collectible_rate = easiest.collectible_rate.lerp(hardest.collectible_rate, t);
+        let hazard_density = easiest.hazard_density.lerp(hardest.hazard_density, t);
+
+        TileRules {
+            tree_chance: rules.tree_chance * hazard_density,
+            apple_chance: rules.apple_chance * collectible_rate,
+            clock_chance: rules.clock_chance * collectible_rate,
+            platform_spacing: easiest
+                .platform_spacing
+                .lerp(hardest.platform_spacing, difficulty),
+            platform_half_width: easiest
+                .platform_half_width
+                .lerp(hardest.platform_half_width, difficulty),
+            max_elements: easiest
+                .max_elements
+                .lerp(hardest.max_elements, difficulty)
+                .round() as usize,
+            physics: rules.physics,
+        }
+        .sanitized()
+    }
+}
+
+/// Piecewise linear lookup, flat past the first and last points.
+fn sample(points: &[Vec2], x: f32) -> f32 {
+    let (Some(first), Some(last)) = (points.first(), points.last()) else {
+        return 0.0;
+    };
+
+    if x <= first.x {
+        return first.y;
+    }
+
+    points
+        .windows(2)
+        .find(|pair| x <= pair[1].x)
+        .map(|pair| {
+            let t = (x - pair[0].x) / (pair[1].x - pair[0].x).max(f32::EPSILON);
+            pair[0].y.lerp(pair[1].y, t)
+        })
+        .unwrap_or(last.y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::worldgen::generate_tile;
+
+    fn curve() -> DifficultyCurve {
+        DifficultyCurve {
+            height: vec![Vec2::new(0.0, 0.0), Vec2::new(4800.0, 0.5)],
+            time: vec![Vec2::new(0.0, 0.0), Vec2::new(60.0, 1.0)],
+            time_step: 30.0,
+            easiest: DifficultySettings {
+                platform_spacing: 100.0,
+                platform_half_width: 50.0,
+                max_elements: 8.0,
+                collectible_rate: 1.0,
+                hazard_density: 1.0,
+            },
+            hardest: DifficultySettings {
+                platform_spacing: 200.0,
+                platform_half_width: 20.0,
+                max_elements: 4.0,
+                collectible_rate: 0.0,
+                hazard_density: 2.0,
+            },
+        }
+    }
+
+    fn tile_rules(curve: &DifficultyCurve, height: f32, elapsed_secs: f32) -> TileRules {
+        let difficulty = curve.difficulty(height, curve.time_bucket(elapsed_secs));
+        curve.apply(TileRules::default(), difficulty)
+    }
+
+    #[test]
+    fn same_seed_builds_the_same_tiles_within_a_time_step() {
+        let curve = curve();
+        for j in 0..10 {
+            let height = j as f32 * 480.0;
+            let tiles = [30.0, 45.0, 59.9]
+                .map(|elapsed| generate_tile(42, 1, j, &tile_rules(&curve, height, elapsed)));
+            assert_eq!(tiles[0], tiles[1], "tile (1, {j}) changed within a step");
+            assert_eq!(tiles[0], tiles[2], "tile (1, {j}) changed within a step");
+        }
+    }
+
+    #[test]
+    fn climbing_time_shapes_the_tiles() {
+        let curve = curve();
+        let early = tile_rules(&curve, 0.0, 0.0);
+        let late = tile_rules(&curve, 0.0, 600.0);
+        assert!(late.platform_spacing > early.platform_spacing);
+        assert!(late.platform_half_width < early.platform_half_width);
+        assert!(late.apple_chance < early.apple_chance);
+        assert!(late.clock_chance < early.clock_chance);
+        assert!(late.tree_chance > early.tree_chance);
+    }
+}
@@ -0,0 +1,178 @@
+use crate::camera::{self, GameCamera};
+use bevy::prelude::*;
+use std::time::Duration;
+
+/// How slow the game runs during a hit-stop, as a fraction of normal speed
+const HIT_STOP_SPEED: f32 = 0.05;
+/// Hit-stop for an impact with an intensity of 1.0, in real time
+const MAX_HIT_STOP: Duration = Duration::from_millis(120);
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_event::<Impact>()
+        .insert_resource(ReducedMotion::default())
+        .insert_resource(HitStop::default())
+        .add_systems(
+            Update,
+            (
+                react_to_impacts.run_if(on_event::<Impact>),
+                end_hit_stop,
+                unshake_camera.before(camera::camera_tracking_system),
+                shake_camera
+                    .after(camera::camera_tracking_system)
+                    .before(camera::parallax_system),
+            ),
+        );
+}
+
+/// Something hit hard enough to feel. `intensity` runs from 0.0 for a bump to 1.0 for the
+/// biggest hit in the game, and scales both the camera shake and the hit-stop.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Impact {
+    pub intensity: f32,
+}
+
+/// Accessibility setting that turns off camera shake and hit-stops.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReducedMotion(pub bool);
+
+/// Trauma based shake for the game camera. Impacts add trauma, which drains over time, and the
+/// shake grows with the square of it so small hits stay subtle.
+#[derive(Component, Debug, Clone)]
+pub struct CameraShake {
+    /// From 0.0 to 1.0
+    pub trauma: f32,
+    /// Trauma lost per second
+    pub decay: f32,
+    /// Furthest the camera moves at full trauma
+    pub max_offset: Vec2,
+    /// Most the camera tilts at full trauma, in radians
+    pub max_angle: f32,
+    /// How fast the shake wobbles
+    pub frequency: f32,
+    elapsed: f32,
+    /// What the last shake added, taken off again before the camera follows the dino
+    applied_offset: Vec2,
+    applied_angle: f32,
+}
+
+impl Default for CameraShake {
+    fn default() -> Self {
+        Self {
+            trauma: 0.0,
+            decay: 1.5,
+            max_offset: Vec2::new(12.0, 10.0),
+            max_angle: 0.03,
+            frequency: 18.0,
+            elapsed: 0.0,
+            applied_offset: Vec2::ZERO,
+            applied_angle: 0.0,
+        }
+    }
+}
+
+impl CameraShake {
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+}
+
+/// Slows time down for a moment after a big hit. Counted in real time, since game time is
+/// what it slows.
+#[derive(Resource, Default, Debug)]
+pub struct HitStop(Option<Timer>);
+
+fn react_to_impacts(
+    mut impacts: EventReader<Impact>,
+    reduced_motion: Res<ReducedMotion>,
+    mut hit_stop: ResMut<HitStop>,
+    mut time: ResMut<Time<Virtual>>,
+    mut shakes: Query<&mut CameraShake, With<GameCamera>>,
+) {
+    if reduced_motion.0 {
+        impacts.clear();
+        return;
+    }
+
+    for impact in impacts.read() {
+        let intensity = impact.intensity.clamp(0.0, 1.0);
+        for mut shake in shakes.iter_mut() {
+            shake.add_trauma(intensity);
+        }
+
+        // A second hit during a hit-stop only ever makes it last longer
+        let duration = MAX_HIT_STOP.mul_f32(intensity);
+        let remaining = hit_stop.0.as_ref().map_or(Duration::ZERO, Timer::remaining);
+        if duration > remaining {
+            hit_stop.0 = Some(Timer::new(duration, TimerMode::Once));
+            time.set_relative_speed(HIT_STOP_SPEED);
+        }
+    }
+}
+
+fn end_hit_stop(
+    real_time: Res<Time<Real>>,
+    reduced_motion: Res<ReducedMotion>,
+    mut hit_stop: ResMut<HitStop>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    let Some(timer) = hit_stop.0.as_mut() else {
+        return;
+    };
+
+    timer.tick(real_time.delta());
+    if timer.finished() || reduced_motion.0 {
+        hit_stop.0 = None;
+        time.set_relative_speed(1.0);
+    }
+}
+
+fn unshake_camera(mut cameras: Query<(&mut Transform, &mut CameraShake), With<GameCamera>>) {
+    for (mut transform, mut shake) in cameras.iter_mut() {
+        transform.translation.x -= shake.applied_offset.x;
+        transform.translation.y -= shake.applied_offset.y;
+        transform.rotate_z(-shake.applied_angle);
+        shake.applied_offset = Vec2::ZERO;
+        shake.applied_angle = 0.0;
+    }
+}
+
+/// Runs on real time so the shake keeps going through a hit-stop, which is when it should
+/// kick in hardest. It still holds still while the game is paused.
+fn shake_camera(
+    real_time: Res<Time<Real>>,
+    virtual_time: Res<Time<Virtual>>,
+    reduced_motion: Res<ReducedMotion>,
+    mut cameras: Query<(&mut Transform, &mut CameraShake), With<GameCamera>>,
+) {
+    let delta = if virtual_time.is_paused() {
+        0.0
+    } else {
+        real_time.delta_secs()
+    };
+
+    for (mut transform, mut shake) in cameras.iter_mut() {
+        if reduced_motion.0 {
+            shake.trauma = 0.0;
+        }
+        shake.trauma = (shake.trauma - shake.decay * delta).max(0.0);
+        if shake.trauma <= 0.0 {
+            continue;
+        }
+
+        shake.elapsed += delta;
+        let t = shake.elapsed * shake.frequency;
+        let amount = shake.trauma * shake.trauma;
+        shake.applied_offset =
+            shake.max_offset * amount * Vec2::new(wobble(t, 0.0), wobble(t, 7.3));
+        shake.applied_angle = shake.max_angle * amount * wobble(t, 13.1);
+
+        transform.translation.x += shake.applied_offset.x;
+        transform.translation.y += shake.applied_offset.y;
+        transform.rotate_z(shake.applied_angle);
+    }
+}
+
+/// Smooth noise from -1.0 to 1.0. Each `seed` gives a different, unrelated looking curve.
+fn wobble(t: f32, seed: f32) -> f32 {
+    ((t + seed).sin() + 0.5 * (2.3 * t + 1.7 * seed).sin()) / 1.5
+}
@@ -1,14 +1,25 @@
-use crate::app::{AppState, DisplayLanguage, RESOLUTION_HEIGHT, RESOLUTION_WIDTH, RUNNING_SPEED};
-use crate::assets::custom::{ImageAssets, SoundAssets};
+use crate::animation::{self, Animator};
+use crate::app::{AppState, DisplayLanguage, RESOLUTION_HEIGHT};
+use crate::assets::custom::{DataAssets, ImageAssets, SoundAssets};
 use crate::assets::lexi::game_over::GameOverLex;
+use crate::biome::{Biome, BiomeArt, BiomeSet};
 use crate::camera;
+use crate::difficulty::{DifficultyCurve, RunTime};
+use crate::feedback::Impact;
+use crate::input::{Action, ActionStrength, PlayerInput};
+use crate::level::{self, SelectedLevel};
+use crate::mode::{GameMode, MaxAltitude, RunOrigin, RunSummary};
+use crate::players::{self, Players, Winner};
+use crate::ruleset::{Physics, Ruleset};
+use crate::spatial::SpatialGrid;
 use crate::util::handles::BODY_FONT;
+use crate::worldgen::{self, GeneratedTile};
 use bevy::ecs::system::Commands;
 use bevy::input::ButtonInput;
 use bevy::input::common_conditions::input_just_pressed;
 
-use bevy::math::bounding::{Aabb2d, IntersectsVolume};
-use bevy::platform::collections::HashMap;
+use bevy::math::bounding::{Aabb2d, BoundingVolume, IntersectsVolume};
+use bevy::platform::collections::{HashMap, HashSet};
 
 use bevy::sprite::Sprite;
 use bevy::ui::{AlignItems, Display, FlexDirection, Node, PositionType, Val};
@@ -21,26 +32,36 @@ use bevy_simple_text_input::{
 use rand::Rng;
 use serde::Deserialize;
 
-const LEADERBOARD_URL: &'static str = env!("LEADERBOARD_URL");
+const LEADERBOARD_URL: &str = env!("LEADERBOARD_URL");
 
 pub(super) fn plugin(app: &mut App) {
     app.init_state::<GameState>()
         .add_event::<SceneChange>()
         .add_event::<RenderHighScores>()
         .add_event::<PostHighScore>()
+        .add_event::<FetchHighScores>()
+        .add_event::<DinoStateChanged>()
         .add_plugins((TextInputPlugin, HttpClientPlugin))
-        .insert_resource(GeneratedPlatformObstacles::default())
-        .insert_resource(GeneratedNonPlatformObstacles::default())
+        .insert_resource(GeneratedTiles::default())
+        .insert_resource(LoadedTiles::default())
+        .insert_resource(TileStreaming::default())
+        .insert_resource(RunSeed::default())
+        .insert_resource(RequestedSeed::default())
+        .insert_resource(ChosenSeed::default())
         .insert_resource(AppleBasket::default())
         .insert_resource(TotalPoints::default())
         .insert_resource(GameTimer::default())
         .insert_resource(TargetHeight::default())
         .insert_resource(GameStatus::default())
         .insert_resource(HighScores::default())
+        .insert_resource(LeaderboardMode::default())
         .insert_resource(PendingSceneChange::default())
         .insert_resource(SfxMusicVolume::default())
         .add_systems(Startup, global_volume_set)
-        .add_systems(OnEnter(AppState::Game), (sfx_setup, setup))
+        .add_systems(
+            OnEnter(AppState::Game),
+            (sfx_setup, setup, camera::spawn_parallax_background),
+        )
         .add_systems(OnEnter(AppState::GameOver), (game_over_scoreboard,))
         .add_systems(Startup, camera::game_camera)
         .add_systems(
@@ -52,18 +73,49 @@ pub(super) fn plugin(app: &mut App) {
                 update_scoreboard,
                 update_healthboard,
                 update_heightboard,
-                spawn_platforms,
-                dino_gravity,
-                arrow_move,
+                spawn_platforms.run_if(level::endless),
+                despawn_far_tiles.run_if(level::endless),
                 camera::camera_tracking_system,
-                camera::parallax_system,
             )
                 .run_if(in_state(AppState::Game).and(in_state(GameState::Running))),
         )
+        // Keeps the background under the camera before the first jump too
+        .add_systems(
+            Update,
+            camera::parallax_system
+                .after(camera::camera_tracking_system)
+                .run_if(in_state(AppState::Game)),
+        )
+        .add_systems(
+            RunFixedMainLoop,
+            (
+                record_dino_input.in_set(RunFixedMainLoopSystem::BeforeFixedMainLoop),
+                interpolate_dino_transform.in_set(RunFixedMainLoopSystem::AfterFixedMainLoop),
+            )
+                .run_if(in_state(AppState::Game).and(in_state(GameState::Running))),
+        )
+        .add_systems(
+            FixedUpdate,
+            (begin_physics_step, dino_gravity, arrow_move)
+                .chain()
+                .run_if(in_state(AppState::Game).and(in_state(GameState::Running))),
+        )
+        .add_systems(
+            Update,
+            dino_state_sounds.run_if(on_event::<DinoStateChanged>),
+        )
+        .add_systems(Update, play_dino_clip.before(animation::animate::<Sprite>))
         .add_systems(Update, post_high_score.run_if(on_event::<PostHighScore>))
+        .add_systems(
+            Update,
+            fetch_high_scores.run_if(on_event::<FetchHighScores>),
+        )
         .add_systems(Update, game_over.run_if(on_event::<SceneChange>))
         .add_systems(Update, scene_transition)
-        .add_systems(FixedUpdate, (fade_out_and_despawn, fade_in_music))
+        .add_systems(
+            FixedUpdate,
+            (fade_out_and_despawn, fade_in_music).run_if(not(in_state(GameState::Paused))),
+        )
         .add_systems(Update, (handle_response, handle_error, button_system))
         .add_systems(
             Update,
@@ -73,12 +125,13 @@ pub(super) fn plugin(app: &mut App) {
         .add_systems(OnEnter(AppState::Menu), (waiting_music, volume_toggle_hud))
         .add_systems(OnEnter(AppState::HighScores), waiting_music)
         .add_systems(OnEnter(AppState::Credits), setup_credits)
+        .add_systems(OnEnter(AppState::Restarting), restart_game)
         .add_systems(
             Update,
             press_space_to_start.run_if(
                 in_state(GameState::NotRunning)
                     .and(in_state(AppState::Game))
-                    .and(input_just_pressed(KeyCode::Space)),
+                    .and(input_just_pressed(Action::Jump)),
             ),
         )
         .add_systems(Update, music_toggle)
@@ -86,10 +139,12 @@ pub(super) fn plugin(app: &mut App) {
 }
 
 #[derive(States, Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[states(scoped_entities)]
 pub enum GameState {
     Running,
     #[default]
     NotRunning,
+    Paused,
 }
 
 #[derive(Component)]
@@ -136,13 +191,13 @@ pub fn toggle_music_on_click(
 ) {
     sfx_music_volume.music = !sfx_music_volume.music;
 
-    if let Ok(mut sprite) = icon.single_mut() {
-        if let Some(atlas) = sprite.texture_atlas.as_mut() {
-            if sfx_music_volume.music {
-                atlas.index = 0;
-            } else {
-                atlas.index = 1;
-            }
+    if let Ok(mut sprite) = icon.single_mut()
+        && let Some(atlas) = sprite.texture_atlas.as_mut()
+    {
+        if sfx_music_volume.music {
+            atlas.index = 0;
+        } else {
+            atlas.index = 1;
         }
     }
 }
@@ -154,13 +209,13 @@ pub fn toggle_sfx_on_click(
 ) {
     sfx_music_volume.sfx = !sfx_music_volume.sfx;
 
-    if let Ok(mut sprite) = icon.single_mut() {
-        if let Some(atlas) = sprite.texture_atlas.as_mut() {
-            if sfx_music_volume.sfx {
-                atlas.index = 0;
-            } else {
-                atlas.index = 1;
-            }
+    if let Ok(mut sprite) = icon.single_mut()
+        && let Some(atlas) = sprite.texture_atlas.as_mut()
+    {
+        if sfx_music_volume.sfx {
+            atlas.index = 0;
+        } else {
+            atlas.index = 1;
         }
     }
 }
@@ -278,18 +333,32 @@ pub fn sfx_setup(
 pub fn setup(
     mut commands: Commands,
     assets: Res<ImageAssets>,
+    data_assets: Res<DataAssets>,
     hud: Res<Hud>,
     mut game_state: ResMut<NextState<GameState>>,
-    mut generated_platforms: ResMut<GeneratedPlatformObstacles>,
-    mut generated_non_platforms: ResMut<GeneratedNonPlatformObstacles>,
+    mut generated_tiles: ResMut<GeneratedTiles>,
+    mut loaded_tiles: ResMut<LoadedTiles>,
     mut game_timer: ResMut<GameTimer>,
     mut total_points: ResMut<TotalPoints>,
     mut apple_basket: ResMut<AppleBasket>,
+    mut grid: ResMut<SpatialGrid>,
+    mut run_seed: ResMut<RunSeed>,
+    mut requested_seed: ResMut<RequestedSeed>,
+    mut chosen_seed: ResMut<ChosenSeed>,
+    ruleset: Res<Ruleset>,
+    players: Res<Players>,
 ) {
     game_state.set(GameState::NotRunning);
-    generated_platforms.0.clear();
-    generated_non_platforms.0.clear();
-    game_timer.0.reset();
+    chosen_seed.0 = requested_seed.0.is_some();
+    run_seed.0 = requested_seed
+        .0
+        .take()
+        .unwrap_or_else(|| rand::rng().random_range(0..100_000_000));
+    info!(seed = run_seed.0, "Starting run");
+    grid.clear();
+    generated_tiles.0.clear();
+    loaded_tiles.0.clear();
+    game_timer.0 = Timer::from_seconds(ruleset.game_time, TimerMode::Once);
     total_points.0 = 0;
     apple_basket.0 = 0;
 
@@ -396,119 +465,47 @@ pub fn setup(
                 ));
             });
 
-        parent.spawn((
-            StateScoped(AppState::Game),
-            Node {
-                position_type: PositionType::Absolute,
-                display: Display::Flex,
-                flex_direction: FlexDirection::Column,
-                left: Val::Px(520.0),
-                top: Val::Px(15.0),
-                width: Val::Px(18.0),
-                height: Val::Px(18.0),
-                ..default()
-            },
-            ImageNode {
-                image: assets.appleicon.clone(),
-                ..default()
-            },
-        ));
-
-        parent
-            .spawn((
-                StateScoped(AppState::Game),
-                Node {
-                    position_type: PositionType::Absolute,
-                    display: Display::Flex,
-                    flex_direction: FlexDirection::Column,
-                    left: Val::Px(550.0),
-                    top: Val::Px(15.0),
+        // Two players stack their apple counters and split the health bars between the corners
+        for player in 0..players.count() {
+            let top = 15.0 + 23.0 * player as f32;
+            spawn_apple_counter(parent, &assets, player, top);
 
-                    ..default()
-                },
-            ))
-            .with_children(|p| {
-                p.spawn((
-                    Scoreboard,
-                    TextFont::from_font(BODY_FONT)
-                        .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 25.),
-                    Text("".into()),
-                ));
-            });
+            let left = match *players {
+                Players::Single => 250.0,
+                Players::Coop | Players::Versus => 60.0 + 360.0 * player as f32,
+            };
+            spawn_health_bar(parent, &assets, player, left);
+        }
+    });
 
-        parent.spawn((
+    for player in 0..players.count() {
+        let start = players.start_offset(player);
+        let mut dino = commands.spawn((
             StateScoped(AppState::Game),
-            Node {
-                position_type: PositionType::Absolute,
-                display: Display::Flex,
-                flex_direction: FlexDirection::Row,
-                left: Val::Px(250.0),
-                top: Val::Px(450.0),
-                // width: Val::Px(18.),
-                // height: Val::Px(18.),
-                align_items: AlignItems::Center,
+            Dino::default(),
+            Animator::new(data_assets.dino_clips.clone(), DinoState::Fall.clip()),
+            DinoInput::default(),
+            PhysicalTranslation(start),
+            PreviousPhysicalTranslation(start),
+            Transform::from_translation(start.extend(0.0)),
+            Player(player),
+            // Add an aabb around the dino for collision detection.
+            Sprite {
+                image: assets.dino.clone(),
+                texture_atlas: Some(TextureAtlas {
+                    layout: assets.dino_layout.clone(),
+                    index: 0,
+                }),
+                color: players::tint(player),
                 ..default()
             },
-            children![
-                (
-                    HealthBar(0),
-                    ImageNode {
-                        image: assets.dinoicon.clone(),
-
-                        ..default()
-                    }
-                ),
-                (
-                    HealthBar(1),
-                    ImageNode {
-                        image: assets.dinoicon.clone(),
-
-                        ..default()
-                    }
-                ),
-                (
-                    HealthBar(2),
-                    ImageNode {
-                        image: assets.dinoicon.clone(),
-
-                        ..default()
-                    }
-                ),
-                (
-                    HealthBar(3),
-                    ImageNode {
-                        image: assets.dinoicon.clone(),
-
-                        ..default()
-                    }
-                ),
-                (
-                    HealthBar(4),
-                    ImageNode {
-                        image: assets.dinoicon.clone(),
-
-                        ..default()
-                    }
-                ),
-            ],
         ));
-    });
 
-    commands.spawn((
-        StateScoped(AppState::Game),
-        Dino::default(),
-        Player,
-        // Add an aabb around the dino for collision detection.
-        Sprite {
-            image: assets.dino.clone(),
-            texture_atlas: Some(TextureAtlas {
-                layout: assets.dino_layout.clone(),
-                index: 0,
-                ..default()
-            }),
-            ..default()
-        },
-    ));
+        // A lone player reads the shared actions, so rebinds and touch controls apply
+        if *players != Players::Single {
+            dino.insert(PlayerInput::new(player));
+        }
+    }
 
     // Add a ground line at 10 above the bottom of the screen.
     // Make this a physics object for ground collisions.
@@ -521,29 +518,10 @@ pub fn setup(
     //     },
     //     Transform::from_xyz(0., -RESOLUTION_HEIGHT / 2. + 10., -1.),
     // ));
+}
 
-    // TODO Tile this automatically based on player postion
-    for i in -2..=2 {
-        for j in -2..=2 {
-            commands.spawn((
-                StateScoped(AppState::Game),
-                camera::Parallax {
-                    x_coeff: 0.6,
-                    y_coeff: 0.6,
-                    x_offset: 600. * 4.,
-                    y_offset: 480. * 4.,
-                    x_tile: i,
-                    y_tile: j,
-                },
-                Sprite {
-                    image: assets.forest_tilemap.clone(),
-                    ..default()
-                },
-                Transform::from_xyz(0., 0., -20.),
-            ));
-        }
-    }
-
+/// Starting platform of the endless climb, hand-authored levels bring their own.
+pub fn spawn_start_platform(commands: &mut Commands) {
     // Add a platform on top of the tree
     commands.spawn((
         StateScoped(AppState::Game),
@@ -566,258 +544,263 @@ pub fn setup(
 #[derive(Component)]
 pub struct HealthBar(pub u32);
 
-fn spawn_platforms(
-    mut commands: Commands,
-    assets: Res<ImageAssets>,
-    player_query: Query<&Transform, With<Player>>,
-    mut platform_obstacle_tiles: ResMut<GeneratedPlatformObstacles>,
-    mut non_platform_obstacle_tiles: ResMut<GeneratedNonPlatformObstacles>,
-) {
-    let Ok(transform) = player_query.single() else {
-        return;
-    };
-    let mut rng = rand::rng();
-
-    let current_x_tile = (transform.translation.x / RESOLUTION_WIDTH).floor() as i32;
-    let current_y_tile = (transform.translation.y / RESOLUTION_HEIGHT).floor() as i32;
-
-    for i in current_x_tile - 2..=current_x_tile + 2 {
-        for j in current_y_tile - 2..=current_y_tile + 2 {
-            // We're within a 600x480 box where we have to spawn obstacles (trees) and
-            // obstacles + platforms (landings) based on a set of rules and randomness.
+/// Marks a HUD element as showing one player's health or apples
+#[derive(Component)]
+pub struct PlayerHud(pub usize);
 
-            // The obstacle-platform (landins) placement rules are:
-            // 1. They should be at least 100 pixels apart from each other in distance.
-            // 2. There should be at least:
-            //      a. one platform max 50 pixels above trees.
-            //      b. one platform max 400 pixels below trees at a max of 300 pixels away from either side of the tree.
+fn spawn_apple_counter(
+    parent: &mut ChildSpawnerCommands,
+    assets: &ImageAssets,
+    player: usize,
+    top: f32,
+) {
+    parent.spawn((
+        StateScoped(AppState::Game),
+        Node {
+            position_type: PositionType::Absolute,
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            left: Val::Px(520.0),
+            top: Val::Px(top),
+            width: Val::Px(18.0),
+            height: Val::Px(18.0),
+            ..default()
+        },
+        ImageNode {
+            image: assets.appleicon.clone(),
+            color: players::tint(player),
+            ..default()
+        },
+    ));
 
-            // The obstacle (tree) placement rules are:
-            // 1. There should be a tree within a landing that's:
-            //    a. max 50 pixels above and 150 pixels away from either side of the landing
-            //    b. max 400 pixels below and 300 pixels away from either side of the landing.
-            // 2. Trees should be at least 200 pixels apart from each other.
+    parent
+        .spawn((
+            StateScoped(AppState::Game),
+            Node {
+                position_type: PositionType::Absolute,
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                left: Val::Px(550.0),
+                top: Val::Px(top),
 
-            if platform_obstacle_tiles.0.get(&(i, j)).is_some() {
-                continue;
-            }
+                ..default()
+            },
+        ))
+        .with_children(|p| {
+            p.spawn((
+                Scoreboard,
+                PlayerHud(player),
+                TextFont::from_font(BODY_FONT).with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 25.),
+                TextColor(players::tint(player)),
+                Text("".into()),
+            ));
+        });
+}
 
-            if non_platform_obstacle_tiles.0.get(&(i, j)).is_some() {
-                continue;
+fn spawn_health_bar(
+    parent: &mut ChildSpawnerCommands,
+    assets: &ImageAssets,
+    player: usize,
+    left: f32,
+) {
+    parent
+        .spawn((
+            StateScoped(AppState::Game),
+            Node {
+                position_type: PositionType::Absolute,
+                display: Display::Flex,
+                flex_direction: FlexDirection::Row,
+                left: Val::Px(left),
+                top: Val::Px(450.0),
+                align_items: AlignItems::Center,
+                ..default()
+            },
+        ))
+        .with_children(|p| {
+            for index in 0..5 {
+                p.spawn((
+                    HealthBar(index),
+                    PlayerHud(player),
+                    ImageNode {
+                        image: assets.dinoicon.clone(),
+                        color: players::tint(player),
+                        ..default()
+                    },
+                ));
             }
+        });
+}
 
-            let mut platform_obstacles = vec![];
-            let mut non_platform_obstacles = vec![];
-
-            // Let's loop within this tile to place landings and trees
-            // There should be a minimum of 4 elements per tile
-            loop {
-                let total_obstacles = platform_obstacles.len() + non_platform_obstacles.len();
-                if total_obstacles >= 4 {
-                    let roll = rng.random_range(0..2);
-                    if roll == 0 {
-                        break;
-                    }
-                }
+fn spawn_platforms(
+    mut commands: Commands,
+    assets: Res<ImageAssets>,
+    data: Res<DataAssets>,
+    biome_sets: Res<Assets<BiomeSet>>,
+    curves: Res<Assets<DifficultyCurve>>,
+    run_time: Res<RunTime>,
+    ruleset: Res<Ruleset>,
+    player_query: Query<&Transform, With<Player>>,
+    streaming: Res<TileStreaming>,
+    run_seed: Res<RunSeed>,
+    mut generated_tiles: ResMut<GeneratedTiles>,
+    mut loaded_tiles: ResMut<LoadedTiles>,
+) {
+    let biomes = biome_sets.get(&data.biomes);
+    let curve = curves.get(&data.difficulty);
+    let fallback = Biome::default();
 
-                if total_obstacles >= 8 {
-                    // Max 8 elements per tile
-                    break;
-                }
+    for (i, j) in tiles_near_players(player_query.iter(), streaming.load_radius) {
+        if loaded_tiles.0.contains(&(i, j)) {
+            continue;
+        }
 
-                if total_obstacles == 0 {
-                    let roll = rng.random_range(0..2);
-                    if roll == 0 {
-                        // Start by placing a platform at a random position within the tile
-                        let platform_x = (i as f32 * RESOLUTION_WIDTH)
-                            + rng.random_range(100.0..(RESOLUTION_WIDTH - 100.0));
-                        let platform_y = (j as f32 * RESOLUTION_HEIGHT)
-                            + rng.random_range(
-                                -RESOLUTION_HEIGHT / 2.0 + 20.0..RESOLUTION_HEIGHT / 2.0 - 20.0,
-                            );
-                        let obstacle = Obstacle {
-                            aabb: Aabb2d::new(
-                                Vec2::new(platform_x, platform_y),
-                                Vec2::new(50., 10.0),
-                            ),
-                        };
+        let biome = biomes.and_then(|set| set.band(j)).unwrap_or(&fallback);
+
+        // Tiles that were streamed out come back exactly as they were left
+        let tile = generated_tiles.0.entry((i, j)).or_insert_with(|| {
+            let mut rules = biome.rules();
+            rules.physics = ruleset.physics;
+            if let Some(curve) = curve {
+                let height = j as f32 * RESOLUTION_HEIGHT;
+                let time_bucket = curve.time_bucket(run_time.0.elapsed_secs());
+                let difficulty = curve.difficulty(height, time_bucket);
+                rules = curve.apply(rules, difficulty);
+            }
+            worldgen::generate_tile(run_seed.0, i, j, &rules)
+        });
 
-                        let roll = rng.random_range(0..16);
+        let art = BiomeArt::at(biomes, j, &assets);
+        spawn_tile(&mut commands, &assets, &art, (i, j), tile);
+        loaded_tiles.0.insert((i, j));
+    }
+}
 
-                        let mut platform = commands.spawn((
-                            StateScoped(AppState::Game),
-                            Platform,
-                            Sprite {
-                                image: assets.leaves.clone(),
-                                // color: bevy::color::palettes::css::GREEN.into(),
-                                // custom_size: Some(Vec2::new(100., 20.)),
-                                ..default()
-                            },
-                            Transform::from_xyz(platform_x, platform_y, -1.),
-                            obstacle.clone(),
-                        ));
+fn spawn_tile(
+    commands: &mut Commands,
+    assets: &ImageAssets,
+    art: &BiomeArt,
+    coordinates: (i32, i32),
+    tile: &GeneratedTile,
+) {
+    for (index, generated) in tile.platforms.iter().enumerate() {
+        let center = generated.aabb.center();
+        let mut platform = commands.spawn((
+            StateScoped(AppState::Game),
+            WorldTile(coordinates),
+            Platform,
+            art.platform.clone(),
+            Transform::from_xyz(center.x, center.y, -1.),
+            Obstacle {
+                aabb: generated.aabb,
+            },
+        ));
 
-                        if roll == 0 {
-                            platform.with_child((
-                                TimeExtender {
-                                    aabb: Aabb2d::new(
-                                        Vec2::new(platform_x, platform_y + 20.0 / 2. + 8.0),
-                                        Vec2::new(8.0, 8.0),
-                                    ),
-                                },
-                                Transform::from_xyz(0., 20.0 / 2.0 + 8.0, -5.),
-                                Sprite {
-                                    image: assets.clock.clone(),
-
-                                    ..default()
-                                },
-                            ));
-                        }
-                        platform_obstacles.push(obstacle)
-                    } else {
-                        // Place a tree at a random position within the tile
-                        let platform_x = (i as f32 * RESOLUTION_WIDTH)
-                            + rng.random_range(50.0..(RESOLUTION_WIDTH - 50.0));
-                        let platform_y = (j as f32 * RESOLUTION_HEIGHT)
-                            + rng.random_range(
-                                -RESOLUTION_HEIGHT / 2.0 + 190.0..RESOLUTION_HEIGHT / 2.0 - 190.0,
-                            );
-                        let obstacle = Obstacle {
-                            aabb: Aabb2d::new(
-                                Vec2::new(platform_x, platform_y),
-                                Vec2::new(25., 190.0),
-                            ),
-                        };
+        if let Some(clock) = generated.collectible {
+            let offset = clock.center() - center;
+            platform.with_child((
+                TimeExtender { aabb: clock },
+                TileCollectible {
+                    tile: coordinates,
+                    index,
+                },
+                Transform::from_xyz(offset.x, offset.y, -5.),
+                Sprite {
+                    image: assets.clock.clone(),
 
-                        let roll = rng.random_range(0..2);
-                        let tree_sprite = if roll == 0 {
-                            // Randomly add an apple tree
-                            // ()
-                            assets.tree.clone()
-                        } else {
-                            // ()
-                            assets.tree.clone()
-                        };
+                    ..default()
+                },
+            ));
+        }
+    }
 
-                        let mut tree = commands.spawn((
-                            StateScoped(AppState::Game),
-                            obstacle.clone(),
-                            Sprite {
-                                image: tree_sprite,
-                                // color: bevy::color::palettes::css::BROWN.into(),
-                                custom_size: Some(Vec2::new(50., 380.)),
-                                ..default()
-                            },
-                            Transform::from_xyz(platform_x, platform_y, -5.),
-                        ));
-                        non_platform_obstacles.push(obstacle);
-
-                        if roll == 0 {
-                            // Randomly add an apple tree
-                            tree.with_child((
-                                Apple {
-                                    aabb: Aabb2d::new(
-                                        Vec2::new(platform_x, platform_y + 380.0 / 2. + 8.0),
-                                        Vec2::new(8.0, 8.0),
-                                    ),
-                                },
-                                Transform::from_xyz(0., 380.0 / 2.0 + 8.0, -5.),
-                                Sprite {
-                                    image: assets.apple.clone(),
-                                    // color: bevy::color::palettes::css::RED.into(),
-                                    custom_size: Some(Vec2::new(30., 30.)),
-                                    ..default()
-                                },
-                            ));
-                        }
-                    }
-                }
+    for (index, generated) in tile.trees.iter().enumerate() {
+        let center = generated.aabb.center();
+        let mut tree = commands.spawn((
+            StateScoped(AppState::Game),
+            WorldTile(coordinates),
+            Obstacle {
+                aabb: generated.aabb,
+            },
+            Sprite {
+                // color: bevy::color::palettes::css::BROWN.into(),
+                custom_size: Some(Vec2::new(50., 380.)),
+                ..art.tree.clone()
+            },
+            Transform::from_xyz(center.x, center.y, -5.),
+        ));
 
-                if platform_obstacles.is_empty() && non_platform_obstacles.is_empty() {
-                    continue;
-                }
+        if let Some(apple) = generated.collectible {
+            let offset = apple.center() - center;
+            tree.with_child((
+                Apple { aabb: apple },
+                TileCollectible {
+                    tile: coordinates,
+                    index,
+                },
+                Transform::from_xyz(offset.x, offset.y, -5.),
+                Sprite {
+                    image: assets.apple.clone(),
+                    // color: bevy::color::palettes::css::RED.into(),
+                    custom_size: Some(Vec2::new(30., 30.)),
+                    ..default()
+                },
+            ));
+        }
+    }
+}
 
-                let roll = rng.random_range(0..2);
-                if roll == 0 {
-                    // Add more elements relative to existing ones
-                    // First try adding a platform relative to existing platforms
-                    for existing_platform in &platform_obstacles {
-                        let roll = rng.random_range(0..50);
-                        if roll == 0 {
-                            // Skip adding more platforms sometimes
-                            continue;
-                        }
-                        let platform_x_offset = rng.random_range(-150.0..150.0);
-                        let platform_y_offset = rng.random_range(-400.0..400.0);
-                        let platform_x = existing_platform.aabb.min.x + platform_x_offset;
-                        let platform_y = existing_platform.aabb.min.y + platform_y_offset;
-                        let obstacle = Obstacle {
-                            aabb: Aabb2d::new(
-                                Vec2::new(platform_x, platform_y),
-                                Vec2::new(50., 10.0),
-                            ),
-                        };
-                        commands.spawn((
-                            StateScoped(AppState::Game),
-                            Platform,
-                            Sprite {
-                                image: assets.leaves.clone(),
-                                // color: bevy::color::palettes::css::GREEN.into(),
-                                // custom_size: Some(Vec2::new(100., 20.)),
-                                ..default()
-                            },
-                            Transform::from_xyz(platform_x, platform_y, -1.),
-                            obstacle.clone(),
-                        ));
-                        platform_obstacles.push(obstacle);
-                        break;
-                    }
+/// Tiles within `radius` tiles of any player, on both axes
+fn tiles_near_players<'a>(
+    players: impl Iterator<Item = &'a Transform>,
+    radius: i32,
+) -> HashSet<(i32, i32)> {
+    let mut tiles = HashSet::new();
+    for transform in players {
+        let (current_x_tile, current_y_tile) = worldgen::tile_at(transform.translation.truncate());
+        for i in current_x_tile - radius..=current_x_tile + radius {
+            for j in current_y_tile - radius..=current_y_tile + radius {
+                tiles.insert((i, j));
+            }
+        }
+    }
+    tiles
+}
 
-                    // Next try adding a platform relative to existing non-platform obstacles (trees)
+/// Despawn tiles that fell outside the unload radius. Their layout stays in `GeneratedTiles`
+/// so `spawn_platforms` can bring them back when a player returns.
+fn despawn_far_tiles(
+    mut commands: Commands,
+    player_query: Query<&Transform, With<Player>>,
+    streaming: Res<TileStreaming>,
+    mut loaded_tiles: ResMut<LoadedTiles>,
+    tile_entities: Query<(Entity, &WorldTile)>,
+) {
+    // With no player there is nothing to measure from, so keep everything
+    if player_query.is_empty() {
+        return;
+    }
 
-                    continue;
-                } else {
-                    // Add tree relative to existing obstacles
-                    for existing_obstacle in &non_platform_obstacles {
-                        let roll = rng.random_range(0..50);
-                        if roll == 0 {
-                            // Skip adding more platforms sometimes
-                            continue;
-                        }
-                        let obstacle_x_offset = rng.random_range(-300.0..300.0);
-                        let obstacle_y_offset = rng.random_range(-400.0..150.0);
-                        let obstacle_x = existing_obstacle.aabb.min.x + obstacle_x_offset;
-                        let obstacle_y = existing_obstacle.aabb.min.y + obstacle_y_offset;
-                        let obstacle = Obstacle {
-                            aabb: Aabb2d::new(
-                                Vec2::new(obstacle_x, obstacle_y),
-                                Vec2::new(25., 190.0),
-                            ),
-                        };
-                        commands.spawn((
-                            StateScoped(AppState::Game),
-                            obstacle.clone(),
-                            Sprite {
-                                image: assets.tree.clone(),
-                                // color: bevy::color::palettes::css::BROWN.into(),
-                                custom_size: Some(Vec2::new(50., 380.)),
-                                ..default()
-                            },
-                            Transform::from_xyz(obstacle_x, obstacle_y, -5.),
-                        ));
-                        non_platform_obstacles.push(obstacle);
-                        break;
-                    }
-                }
-            }
+    let near = tiles_near_players(player_query.iter(), streaming.unload_radius);
+    let far_tiles = loaded_tiles
+        .0
+        .iter()
+        .filter(|tile| !near.contains(*tile))
+        .copied()
+        .collect::<Vec<_>>();
 
-            platform_obstacle_tiles.0.insert((i, j), platform_obstacles);
+    if far_tiles.is_empty() {
+        return;
+    }
 
-            non_platform_obstacle_tiles
-                .0
-                .insert((i, j), non_platform_obstacles);
+    for (entity, world_tile) in tile_entities.iter() {
+        if far_tiles.contains(&world_tile.0) {
+            commands.entity(entity).despawn();
         }
     }
+
+    for tile in far_tiles {
+        loaded_tiles.0.remove(&tile);
+    }
 }
 
 #[derive(Resource)]
@@ -825,7 +808,10 @@ pub struct GameTimer(pub Timer);
 
 impl Default for GameTimer {
     fn default() -> Self {
-        Self(Timer::from_seconds(200., TimerMode::Once))
+        Self(Timer::from_seconds(
+            Ruleset::default().game_time,
+            TimerMode::Once,
+        ))
     }
 }
 
@@ -834,30 +820,70 @@ pub struct TargetHeight(pub f32);
 
 impl Default for TargetHeight {
     fn default() -> Self {
-        Self(9822.) // Makes 10k when the dino lands on the starting platform
+        Self(Ruleset::default().target_height)
     }
 }
 
 #[derive(Component)]
 pub struct Scoreboard;
 
-#[derive(Component)]
-pub struct Healthboard;
-
 #[derive(Component)]
 pub struct Timeboard;
 
 #[derive(Component)]
 pub struct Heightboard;
 
+/// Seed for the current run. Every generated tile derives its own rng from this, so the same
+/// seed always produces the same world.
+#[derive(Resource, Default, Debug, Clone, Copy)]
+pub struct RunSeed(pub u64);
+
+/// Seed to use for the next run instead of rolling a new one
+#[derive(Resource, Default, Debug)]
+pub struct RequestedSeed(pub Option<u64>);
+
+/// Whether the current run's seed was typed in or replayed instead of rolled
+#[derive(Resource, Default, Debug)]
+pub struct ChosenSeed(pub bool);
+
 #[derive(Resource, Default)]
-pub struct GeneratedPlatformObstacles(pub HashMap<(i32, i32), Vec<Obstacle>>);
+pub struct GeneratedTiles(pub HashMap<(i32, i32), GeneratedTile>);
 
+/// Tiles that currently have entities in the world
 #[derive(Resource, Default)]
-pub struct GeneratedNonPlatformObstacles(pub HashMap<(i32, i32), Vec<Obstacle>>);
+pub struct LoadedTiles(pub HashSet<(i32, i32)>);
+
+#[derive(Resource)]
+pub struct TileStreaming {
+    /// Tiles within this many tiles of the player are spawned
+    pub load_radius: i32,
+    /// Tiles further than this are despawned. Keep it above `load_radius` so tiles on the
+    /// border don't flicker in and out.
+    pub unload_radius: i32,
+}
+
+impl Default for TileStreaming {
+    fn default() -> Self {
+        Self {
+            load_radius: 2,
+            unload_radius: 3,
+        }
+    }
+}
 
 #[derive(Component)]
-pub struct Player;
+pub struct WorldTile(pub (i32, i32));
+
+/// Points a collectible back at its record in `GeneratedTiles`
+#[derive(Component)]
+pub struct TileCollectible {
+    pub tile: (i32, i32),
+    pub index: usize,
+}
+
+/// A dino someone is playing, by player index. Single player runs only have player 0.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Player(pub usize);
 
 #[derive(Component, Debug, Clone)]
 pub struct Platform;
@@ -885,77 +911,245 @@ pub struct TotalPoints(u32);
 
 #[derive(Component, Debug, Clone)]
 pub struct Dino {
-    pub timer: Timer,
+    pub state: DinoState,
+    /// Seconds spent in `state`
+    pub state_time: f32,
     pub walk_sound_effect_timer: Timer,
-    pub idle_frame_forward: bool,
-    pub grounded: bool,
     pub velocity: Vec2,
-    pub jumping: bool,
+    /// Seconds the current jump has been pushed up for, infinite once the push is spent
     pub jump_time: f32,
     pub jump_height: f32,
-    pub attacking: bool,
-    pub can_attack: bool,
-    pub frame_hold_counter: Vec<(usize, u8, u8)>,
     pub aabb: Aabb2d,
     pub health: i32,
+    /// Apples this dino picked, `AppleBasket` holds the whole run's
+    pub apples: u32,
+    /// Seconds after walking off a platform during which a jump is still allowed
+    pub coyote_time: f32,
+    /// Seconds a jump press is remembered while airborne so it fires on landing
+    pub jump_buffer_time: f32,
+    pub time_since_grounded: f32,
+    pub time_since_jump_pressed: f32,
 }
 
 impl Default for Dino {
     fn default() -> Self {
         Self {
-            timer: Timer::from_seconds(0.07, TimerMode::Repeating),
+            state: DinoState::Fall,
+            state_time: 0.0,
             walk_sound_effect_timer: Timer::from_seconds(0.15, TimerMode::Repeating),
-            idle_frame_forward: true,
-            grounded: false,
             velocity: Vec2::ZERO,
-            jumping: false,
-            attacking: false,
-            jump_height: 1500.0,
-            frame_hold_counter: vec![(21, 0, 1)],
-            can_attack: false,
-            jump_time: 0.0,
+            jump_height: Physics::default().jump_height,
+            jump_time: f32::INFINITY,
             health: 100,
-            aabb: Aabb2d::new(Vec2::ZERO, Vec2::new(32., 32.)),
+            apples: 0,
+            aabb: Dino::aabb_at(Vec2::ZERO),
+            coyote_time: 0.1,
+            jump_buffer_time: 0.12,
+            time_since_grounded: f32::INFINITY,
+            time_since_jump_pressed: f32::INFINITY,
+        }
+    }
+}
+
+/// What the dino is doing. The physics steps are the only place it changes, and every change
+/// is announced with a `DinoStateChanged` so nothing else has to work it out again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DinoState {
+    Idle,
+    Walk,
+    /// Rising, or only just past the top of the jump
+    Jump,
+    Fall,
+    /// Swinging mid-air
+    Attack,
+    /// Touched down, lasts `Dino::LAND_TIME`
+    Land,
+    /// Touched down hard enough to take damage, lasts `Dino::HURT_TIME`
+    Hurt,
+}
+
+impl DinoState {
+    /// The clip in `data/dino.clips.json` that plays in this state
+    pub fn clip(self) -> &'static str {
+        match self {
+            DinoState::Idle => "idle",
+            DinoState::Walk => "walk",
+            DinoState::Jump => "jump",
+            DinoState::Fall => "fall",
+            DinoState::Attack => "attack",
+            DinoState::Land => "land",
+            DinoState::Hurt => "hurt",
+        }
+    }
+
+    pub fn is_grounded(self) -> bool {
+        matches!(
+            self,
+            DinoState::Idle | DinoState::Walk | DinoState::Land | DinoState::Hurt
+        )
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DinoStateChanged {
+    pub from: DinoState,
+    pub to: DinoState,
+}
+
+impl Dino {
+    pub const HALF_SIZE: Vec2 = Vec2::new(32., 32.);
+
+    pub fn aabb_at(position: Vec2) -> Aabb2d {
+        Aabb2d::new(position, Self::HALF_SIZE)
+    }
+
+    /// Falling faster than this switches from the jump to the fall pose
+    pub const FALL_SPEED: f32 = -200.0;
+    /// Seconds a mid-air swing lasts
+    pub const ATTACK_TIME: f32 = 0.56;
+    pub const LAND_TIME: f32 = 0.1;
+    pub const HURT_TIME: f32 = 0.4;
+
+    /// Height above standing on the starting platform
+    pub fn altitude(y: f32) -> f32 {
+        y - (-RESOLUTION_HEIGHT / 2. + 30. + Self::HALF_SIZE.y)
+    }
+
+    pub fn grounded(&self) -> bool {
+        self.state.is_grounded()
+    }
+
+    /// Moves to `state` and announces it. Staying in the same state is not a change.
+    pub fn set_state(
+        &mut self,
+        state: DinoState,
+        state_changes: &mut EventWriter<DinoStateChanged>,
+    ) {
+        if self.state == state {
+            return;
         }
+
+        state_changes.write(DinoStateChanged {
+            from: self.state,
+            to: state,
+        });
+        self.state = state;
+        self.state_time = 0.0;
+    }
+}
+
+/// Input sampled every frame and consumed by the fixed timestep. Presses are latched so a tap
+/// that happens between two fixed steps is never lost.
+#[derive(Component, Debug, Default)]
+pub struct DinoInput {
+    /// From -1.0 (left) to 1.0 (right), analog sticks give anything in between
+    pub move_x: f32,
+    pub jump_held: bool,
+    pub jump_pressed: bool,
+    pub attack_pressed: bool,
+}
+
+/// Position of the dino as seen by the fixed timestep. `Transform` is only ever an interpolation
+/// between the previous and current physics positions.
+#[derive(Component, Debug, Default, Clone, Copy, Deref, DerefMut)]
+pub struct PhysicalTranslation(pub Vec2);
+
+#[derive(Component, Debug, Default, Clone, Copy, Deref, DerefMut)]
+pub struct PreviousPhysicalTranslation(pub Vec2);
+
+fn record_dino_input(
+    actions: Res<ButtonInput<Action>>,
+    strength: Res<ActionStrength>,
+    mut dino: Query<(&mut DinoInput, Option<&PlayerInput>), With<Dino>>,
+) {
+    for (mut input, player_input) in dino.iter_mut() {
+        let (actions, strength) = match player_input {
+            Some(player_input) => (&player_input.actions, &player_input.strength),
+            None => (&*actions, &*strength),
+        };
+
+        input.move_x = strength.move_x();
+        input.jump_held = actions.pressed(Action::Jump);
+        input.jump_pressed |= actions.just_pressed(Action::Jump);
+        input.attack_pressed |= actions.just_pressed(Action::Attack);
     }
 }
 
+fn begin_physics_step(
+    mut dino: Query<(
+        &mut Dino,
+        &PhysicalTranslation,
+        &mut PreviousPhysicalTranslation,
+    )>,
+) {
+    for (mut dino, position, mut previous_position) in dino.iter_mut() {
+        previous_position.0 = position.0;
+        // The aabb follows the interpolated transform between steps, so snap it back to the
+        // physics position before any collision checks.
+        dino.aabb = Dino::aabb_at(position.0);
+    }
+}
+
+fn interpolate_dino_transform(
+    fixed_time: Res<Time<Fixed>>,
+    mut dino: Query<(
+        &mut Transform,
+        &mut Dino,
+        &PhysicalTranslation,
+        &PreviousPhysicalTranslation,
+    )>,
+) {
+    let alpha = fixed_time.overstep_fraction();
+    for (mut transform, mut dino, position, previous_position) in dino.iter_mut() {
+        let rendered = previous_position.0.lerp(position.0, alpha);
+        transform.translation.x = rendered.x;
+        transform.translation.y = rendered.y;
+        dino.aabb = Dino::aabb_at(rendered);
+    }
+}
+
+/// The circle wipe between scenes. The scene changes underneath once the wipe reaches
+/// `pause_frame`, and the wipe is gone when its clip finishes.
 #[derive(Component)]
 pub struct Transition {
-    pub timer: Timer,
-    pub last_frame: usize,
     pub pause_frame: usize,
+    pub covered: bool,
 }
 
 impl Transition {
-    pub fn new(last_frame: usize, pause_frame: usize) -> Self {
+    pub fn new(pause_frame: usize) -> Self {
         Self {
-            timer: Timer::from_seconds(0.1, TimerMode::Repeating),
-            last_frame: last_frame,
-            pause_frame: pause_frame,
+            pause_frame,
+            covered: false,
         }
     }
 }
 
 fn dino_gravity(
-    mut dino: Query<(&mut Transform, &mut Dino), With<Sprite>>,
+    mut dino: Query<(&mut PhysicalTranslation, &mut Dino), With<Sprite>>,
     platforms: Query<&Obstacle, With<Platform>>,
+    grid: Res<SpatialGrid>,
     time: Res<Time>,
     mut commands: Commands,
     sound_assets: Res<SoundAssets>,
     sfx_music_volume: Res<SfxMusicVolume>,
+    ruleset: Res<Ruleset>,
+    game_mode: Res<GameMode>,
+    mut state_changes: EventWriter<DinoStateChanged>,
+    mut impacts: EventWriter<Impact>,
 ) {
-    if let Ok((mut transform, mut dino)) = dino.single_mut() {
-        let gravity = -1200.0;
+    for (mut position, mut dino) in dino.iter_mut() {
+        let gravity = ruleset.physics.gravity;
 
         // Apply gravity if not grounded
-        if !dino.grounded {
+        if !dino.grounded() {
             dino.velocity.y += gravity * time.delta_secs();
         }
 
         // Apply velocity
+        let previous_bottom = dino.aabb.min.y;
         let dy = dino.velocity.y * time.delta_secs();
-        transform.translation.y += dy;
+        position.y += dy;
         dino.aabb.min.y += dy;
         dino.aabb.max.y += dy;
 
@@ -972,62 +1166,94 @@ fn dino_gravity(
         //     dino.grounded = true;
         // } else {
         // Check collision with obstacles (platforms)
-        let mut landed = false;
-        for platform in platforms.iter() {
-            // First check if this obstacle is withint the x range of the dino
-            let dino_left = dino.aabb.min.x;
-            let dino_right = dino.aabb.max.x;
-            let obstacle_left = platform.aabb.min.x;
-            let obstacle_right = platform.aabb.max.x;
-            if dino_right < obstacle_left || dino_left > obstacle_right {
-                continue;
-            }
-
-            // Now check if the dino is landing on the platform
-            if dino.velocity.y <= 0.0 {
-                if dino.aabb.min.y >= platform.aabb.max.y + 15.0 {
+        let mut landing: Option<f32> = None;
+        if dino.velocity.y <= 0.0 {
+            let swept = Aabb2d {
+                min: dino.aabb.min,
+                max: Vec2::new(dino.aabb.max.x, previous_bottom.max(dino.aabb.min.y) + 15.0),
+            };
+            for platform in grid
+                .query(&swept)
+                .into_iter()
+                .filter_map(|entity| platforms.get(entity).ok())
+            {
+                // First check if this obstacle is withint the x range of the dino
+                let dino_left = dino.aabb.min.x;
+                let dino_right = dino.aabb.max.x;
+                let obstacle_left = platform.aabb.min.x;
+                let obstacle_right = platform.aabb.max.x;
+                if dino_right < obstacle_left || dino_left > obstacle_right {
                     continue;
-                } else if dino.aabb.min.y < platform.aabb.max.y {
+                }
+
+                // Sweep the bottom of the dino from where it started this step to where it
+                // ended up. Any platform top inside that span (plus the 15px landing band above
+                // it) was crossed, no matter how fast the fall was.
+                let platform_top = platform.aabb.max.y;
+                if previous_bottom < platform_top || dino.aabb.min.y >= platform_top + 15.0 {
                     continue;
-                } else {
-                    // Snap the dino to the top of the platform
-                    let dino_height = dino.aabb.max.y - dino.aabb.min.y;
-                    let dino_half_height = dino_height / 2.0;
-                    let dino_new_y = platform.aabb.max.y + dino_half_height;
-                    transform.translation.y = dino_new_y;
-                    dino.aabb.min.y = platform.aabb.max.y;
-                    dino.aabb.max.y = platform.aabb.max.y + dino_height;
-
-                    if dino.velocity.y < -1500.0 {
-                        let mut rng = rand::rng();
-                        let roll = rng.random_range(1..3);
-                        let sfx = if roll == 1 {
-                            sound_assets.thud1.clone()
-                        } else if roll == 2 {
-                            sound_assets.thud2.clone()
-                        } else {
-                            sound_assets.thud3.clone()
-                        };
+                }
 
-                        let vol = if sfx_music_volume.sfx { 2.0 } else { 0.0 };
+                // When falling, the highest top is the first one crossed
+                if landing.is_none_or(|top| platform_top > top) {
+                    landing = Some(platform_top);
+                }
+            }
+        }
 
-                        commands.spawn((
-                            PlaybackSettings::DESPAWN.with_volume(bevy::audio::Volume::Linear(vol)),
-                            AudioPlayer(sfx),
-                        ));
+        let landed = landing.is_some();
+        if let Some(platform_top) = landing {
+            // Snap the dino to the top of the platform
+            let dino_height = dino.aabb.max.y - dino.aabb.min.y;
+            let dino_half_height = dino_height / 2.0;
+            let dino_new_y = platform_top + dino_half_height;
+            position.y = dino_new_y;
+            dino.aabb.min.y = platform_top;
+            dino.aabb.max.y = platform_top + dino_height;
+
+            let mut hurt = false;
+            if let Some(damage) = ruleset.fall_damage.damage(dino.velocity.y) {
+                // Just hard enough to hurt is a medium hit, twice that is the biggest
+                let min_speed = ruleset.fall_damage.min_speed;
+                impacts.write(Impact {
+                    intensity: (-dino.velocity.y / (2.0 * min_speed)).min(1.0),
+                });
+
+                let mut rng = rand::rng();
+                let roll = rng.random_range(1..3);
+                let sfx = if roll == 1 {
+                    sound_assets.thud1.clone()
+                } else if roll == 2 {
+                    sound_assets.thud2.clone()
+                } else {
+                    sound_assets.thud3.clone()
+                };
 
-                        let damage = 100 / 5 * ((dino.velocity.y / 500.).abs().floor() as i32 - 2);
-                        dino.health -= damage;
-                    }
+                let vol = if sfx_music_volume.sfx { 2.0 } else { 0.0 };
 
-                    dino.velocity.y = 0.0;
-                    dino.grounded = true;
-                    landed = true;
+                commands.spawn((
+                    PlaybackSettings::DESPAWN.with_volume(bevy::audio::Volume::Linear(vol)),
+                    AudioPlayer(sfx),
+                ));
+
+                if game_mode.has_fall_damage() {
+                    dino.health -= damage;
+                    hurt = true;
                 }
             }
+
+            dino.velocity.y = 0.0;
+            if !dino.grounded() {
+                let state = if hurt {
+                    DinoState::Hurt
+                } else {
+                    DinoState::Land
+                };
+                dino.set_state(state, &mut state_changes);
+            }
         }
-        if !landed {
-            dino.grounded = false;
+        if !landed && dino.grounded() {
+            dino.set_state(DinoState::Fall, &mut state_changes);
         }
     }
     // }
@@ -1036,251 +1262,258 @@ fn dino_gravity(
 // In arrow_move, add a query for the tree's Aabb:
 pub fn arrow_move(
     time: Res<Time>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut dino: Query<(&mut Transform, &mut Sprite, &mut Dino), With<Sprite>>,
-    obstacles: Query<&Obstacle>,
+    mut dino: Query<
+        (
+            &mut PhysicalTranslation,
+            &mut Sprite,
+            &mut Dino,
+            &mut DinoInput,
+        ),
+        With<Sprite>,
+    >,
+    obstacles: Query<(&Obstacle, Has<Platform>)>,
+    grid: Res<SpatialGrid>,
     mut commands: Commands,
     sound_assets: Res<SoundAssets>,
     sfx_music_volume: Res<SfxMusicVolume>,
+    ruleset: Res<Ruleset>,
+    mut state_changes: EventWriter<DinoStateChanged>,
+    mut impacts: EventWriter<Impact>,
 ) {
     let mut rng = rand::rng();
-    if let Ok((mut transform, mut sprite, mut dino)) = dino.single_mut() {
-        dino.timer.tick(time.delta());
+    let physics = ruleset.physics;
+    for (mut position, mut sprite, mut dino, mut input) in dino.iter_mut() {
         dino.walk_sound_effect_timer.tick(time.delta());
+        dino.state_time += time.delta_secs();
 
-        // Start jump
-        if keyboard_input.just_pressed(KeyCode::Space) && dino.grounded {
-            let roll = rng.random_range(1..2);
-            let sfx = if roll == 1 {
-                sound_assets.boingjump1.clone()
-            } else {
-                sound_assets.boingjump2.clone()
-            };
+        let jump_pressed = std::mem::take(&mut input.jump_pressed);
+        // Jumping again mid-air attacks too, the attack button just doesn't need a jump first
+        let attack_pressed = std::mem::take(&mut input.attack_pressed) || jump_pressed;
 
-            let vol = if sfx_music_volume.sfx { 0.5 } else { 0.0 };
-            commands.spawn((
-                PlaybackSettings::DESPAWN.with_volume(bevy::audio::Volume::Linear(vol)),
-                AudioPlayer(sfx),
-            ));
+        if jump_pressed {
+            dino.time_since_jump_pressed = 0.0;
+        } else {
+            dino.time_since_jump_pressed += time.delta_secs();
+        }
+        if dino.grounded() {
+            dino.time_since_grounded = 0.0;
+        } else {
+            dino.time_since_grounded += time.delta_secs();
+        }
 
-            dino.jumping = true;
+        // A press shortly before landing still counts, and so does one shortly after
+        // walking off a platform.
+        let wants_jump = dino.time_since_jump_pressed <= dino.jump_buffer_time;
+        let can_jump = dino.grounded() || dino.time_since_grounded <= dino.coyote_time;
+
+        // Start jump
+        let jumped = wants_jump && can_jump;
+        if jumped {
+            dino.set_state(DinoState::Jump, &mut state_changes);
             dino.jump_time = 0.0;
-            dino.grounded = false;
-            dino.attacking = false;
-            dino.can_attack = false;
-            dino.jump_height = 1500.0;
+            dino.jump_height = physics.jump_height;
+            // Spend both windows so one press can't produce a second jump
+            dino.time_since_jump_pressed = f32::INFINITY;
+            dino.time_since_grounded = f32::INFINITY;
         }
 
-        let gravity = -1200.0_f32;
-        let max_jump_time = 0.22; // seconds, tune for feel
+        let gravity = physics.gravity;
+        let max_jump_time = physics.max_jump_time;
         let jump_acceleration = (2.0 * dino.jump_height * gravity.abs()).sqrt() * max_jump_time;
 
-        // Continue jump while holding space and not exceeding max jump time
-        if dino.jumping && keyboard_input.pressed(KeyCode::Space) && dino.jump_time < max_jump_time
-        {
+        // Continue jump while holding space and not exceeding max jump time. The first step
+        // always applies so a buffered tap that was released before landing still hops. Letting
+        // go spends what is left of the push.
+        if dino.jump_time < max_jump_time && (input.jump_held || dino.jump_time == 0.0) {
             dino.velocity.y = jump_acceleration;
             dino.jump_time += time.delta_secs();
         } else {
-            dino.jumping = false;
+            dino.jump_time = f32::INFINITY;
         }
 
-        // Horizontal movement input
-        let mut target_velocity_x = 0.0;
-        if keyboard_input.any_pressed([KeyCode::ArrowRight, KeyCode::KeyD]) {
+        // Horizontal movement input, a half-tilted stick walks at half speed
+        let target_velocity_x = input.move_x * physics.running_speed;
+        if input.move_x > 0.0 {
             sprite.flip_x = false;
-            target_velocity_x = RUNNING_SPEED;
-        } else if keyboard_input.any_pressed([KeyCode::ArrowLeft, KeyCode::KeyA]) {
+        } else if input.move_x < 0.0 {
             sprite.flip_x = true;
-            target_velocity_x = -RUNNING_SPEED;
         }
 
         // Dampening factor (0.0 = instant, 1.0 = no change)
-        let dampening = 0.95;
+        let dampening = physics.dampening;
         dino.velocity.x = dino.velocity.x * dampening + target_velocity_x * (1.0 - dampening);
 
         // Apply velocity to position
         let dx = dino.velocity.x * time.delta_secs();
-        transform.translation.x += dx;
+        position.x += dx;
         dino.aabb.min.x += dx;
         dino.aabb.max.x += dx;
 
-        let x_collision = obstacles
-            .iter()
-            .any(|obstacle| dino.aabb.intersects(&obstacle.aabb));
+        // Touching anything counts as a collision for the attack bounce, but only trees push
+        // back. Platforms are one-way and are only solid from above (see `dino_gravity`).
+        let mut x_collision = false;
+        let nearby = grid.query(&dino.aabb);
+        for (obstacle, is_platform) in nearby
+            .into_iter()
+            .filter_map(|entity| obstacles.get(entity).ok())
+        {
+            if !dino.aabb.intersects(&obstacle.aabb) {
+                continue;
+            }
+            x_collision = true;
 
-        // if x_collision {
-        //     // Simple collision response: reset to previous position
-        //     transform.translation.x -= dx;
-        //     dino.aabb.min.x -= dx;
-        //     dino.aabb.max.x -= dx;
-        //     dino.velocity.x = 0.0;
-        // }
+            if is_platform {
+                continue;
+            }
 
-        if dino.jumping || !dino.grounded {
-            if keyboard_input.just_pressed(KeyCode::Space) && !dino.attacking && dino.can_attack {
-                // Run animation 18-24 for attack
+            // Push the dino out to whichever side of the trunk it is on
+            let push = if dino.aabb.center().x < obstacle.aabb.center().x {
+                obstacle.aabb.min.x - dino.aabb.max.x
+            } else {
+                obstacle.aabb.max.x - dino.aabb.min.x
+            };
+            position.x += push;
+            dino.aabb.min.x += push;
+            dino.aabb.max.x += push;
+            dino.velocity.x = 0.0;
+        }
 
-                let roll = rng.random_range(1..4);
-                let sfx = if roll == 1 {
-                    sound_assets.swoosh1.clone()
-                } else if roll == 2 {
-                    sound_assets.swoosh2.clone()
-                } else if roll == 3 {
-                    sound_assets.swoosh3.clone()
-                } else {
-                    sound_assets.swoosh4.clone()
-                };
+        match dino.state {
+            DinoState::Jump | DinoState::Fall | DinoState::Attack => {
+                // The press that started this jump isn't also a swing
+                if attack_pressed && dino.state != DinoState::Attack && !jumped {
+                    dino.set_state(DinoState::Attack, &mut state_changes);
 
-                let vol = if sfx_music_volume.sfx { 0.5 } else { 0.0 };
+                    if x_collision {
+                        let roll = rng.random_range(1..3);
+                        let sfx = if roll == 1 {
+                            sound_assets.impact1.clone()
+                        } else if roll == 2 {
+                            sound_assets.impact2.clone()
+                        } else {
+                            sound_assets.impact3.clone()
+                        };
+                        let vol = if sfx_music_volume.sfx { 0.25 } else { 0.0 };
 
-                commands.spawn((
-                    PlaybackSettings::DESPAWN.with_volume(bevy::audio::Volume::Linear(vol)),
-                    AudioPlayer(sfx),
-                ));
-                // Simulate an impact for todo code
-                dino.attacking = true;
+                        commands.spawn((
+                            PlaybackSettings::DESPAWN.with_volume(bevy::audio::Volume::Linear(vol)),
+                            AudioPlayer(sfx),
+                        ));
+                        impacts.write(Impact { intensity: 0.35 });
+                        // Hitting something bounces the dino up like a fresh jump
+                        dino.jump_time = 0.0;
+                    }
+                } else if dino.state == DinoState::Attack {
+                    if dino.state_time >= Dino::ATTACK_TIME {
+                        let state = if dino.velocity.y > Dino::FALL_SPEED {
+                            DinoState::Jump
+                        } else {
+                            DinoState::Fall
+                        };
+                        dino.set_state(state, &mut state_changes);
+                    }
+                } else if dino.state == DinoState::Jump && dino.velocity.y <= Dino::FALL_SPEED {
+                    dino.set_state(DinoState::Fall, &mut state_changes);
+                }
+            }
+            DinoState::Idle | DinoState::Walk | DinoState::Land | DinoState::Hurt => {
+                let settled = match dino.state {
+                    DinoState::Land => dino.state_time >= Dino::LAND_TIME,
+                    DinoState::Hurt => dino.state_time >= Dino::HURT_TIME,
+                    _ => true,
+                };
+                if settled {
+                    let state = if input.move_x != 0.0 {
+                        DinoState::Walk
+                    } else {
+                        DinoState::Idle
+                    };
+                    dino.set_state(state, &mut state_changes);
+                }
 
-                if x_collision {
-                    let roll = rng.random_range(1..3);
+                if dino.state == DinoState::Walk && dino.walk_sound_effect_timer.just_finished() {
+                    let roll = rng.random_range(1..10);
                     let sfx = if roll == 1 {
-                        sound_assets.impact1.clone()
+                        sound_assets.walk1.clone()
                     } else if roll == 2 {
-                        sound_assets.impact2.clone()
+                        sound_assets.walk2.clone()
+                    } else if roll == 3 {
+                        sound_assets.walk3.clone()
+                    } else if roll == 4 {
+                        sound_assets.walk4.clone()
+                    } else if roll == 5 {
+                        sound_assets.walk5.clone()
+                    } else if roll == 6 {
+                        sound_assets.walk6.clone()
+                    } else if roll == 7 {
+                        sound_assets.walk7.clone()
+                    } else if roll == 8 {
+                        sound_assets.walk8.clone()
+                    } else if roll == 9 {
+                        sound_assets.walk9.clone()
                     } else {
-                        sound_assets.impact3.clone()
+                        sound_assets.walk10.clone()
                     };
-                    let vol = if sfx_music_volume.sfx { 0.25 } else { 0.0 };
+
+                    let vol = if sfx_music_volume.sfx { 1.0 } else { 0.0 };
 
                     commands.spawn((
-                        PlaybackSettings::DESPAWN.with_volume(bevy::audio::Volume::Linear(vol)),
+                        PlaybackSettings::DESPAWN.with_volume(audio::Volume::Linear(vol)),
                         AudioPlayer(sfx),
                     ));
-                    dino.jump_time = 0.0;
-                    dino.jumping = true;
                 }
+            }
+        }
+    }
+}
 
-                if let Some(atlas) = sprite.texture_atlas.as_mut() {
-                    atlas.index = 18;
-                };
-            } else if dino.attacking {
-                if dino.timer.just_finished() {
-                    if let Some(atlas) = sprite.texture_atlas.as_mut() {
-                        if atlas.index == 24 {
-                            atlas.index = 23;
-                            dino.attacking = false; // End attack animation
-                        } else {
-                            // Change this to a loop instead of selecting index 0
-
-                            for hold in dino.frame_hold_counter.iter_mut() {
-                                if hold.0 == atlas.index {
-                                    // Hold this frame for N extra ticks
-                                    if hold.1 < hold.2 {
-                                        hold.1 += 1;
-                                    } else {
-                                        hold.1 = 0;
-                                        atlas.index += 1;
-                                    }
-                                } else {
-                                    atlas.index += 1;
-                                }
-                            }
-                        }
-                    };
-                }
-            } else if dino.velocity.y > -200.0 {
-                if let Some(atlas) = sprite.texture_atlas.as_mut() {
-                    atlas.index = 25; // Jumping frame
-                };
-            } else {
-                if let Some(atlas) = sprite.texture_atlas.as_mut() {
-                    atlas.index = 23; // Falling frame
+fn play_dino_clip(mut dino: Query<(&Dino, &mut Animator)>) {
+    for (dino, mut animator) in dino.iter_mut() {
+        animator.play(dino.state.clip());
+    }
+}
+
+/// Jump and swing sounds, played on the way into the state so every way in sounds the same.
+fn dino_state_sounds(
+    mut commands: Commands,
+    mut state_changes: EventReader<DinoStateChanged>,
+    sound_assets: Res<SoundAssets>,
+    sfx_music_volume: Res<SfxMusicVolume>,
+) {
+    let mut rng = rand::rng();
+    for change in state_changes.read() {
+        let (sfx, vol) = match change.to {
+            // Coming out of a swing is still the same jump
+            DinoState::Jump if change.from != DinoState::Attack => {
+                let roll = rng.random_range(1..2);
+                let sfx = if roll == 1 {
+                    sound_assets.boingjump1.clone()
+                } else {
+                    sound_assets.boingjump2.clone()
                 };
+                (sfx, 0.5)
             }
-            dino.can_attack = true;
-        } else if keyboard_input.any_pressed([
-            KeyCode::ArrowRight,
-            KeyCode::KeyD,
-            KeyCode::ArrowLeft,
-            KeyCode::KeyA,
-        ]) {
-            // Walking state
-            if dino.walk_sound_effect_timer.just_finished() {
-                let roll = rng.random_range(1..10);
+            DinoState::Attack => {
+                let roll = rng.random_range(1..4);
                 let sfx = if roll == 1 {
-                    sound_assets.walk1.clone()
+                    sound_assets.swoosh1.clone()
                 } else if roll == 2 {
-                    sound_assets.walk2.clone()
+                    sound_assets.swoosh2.clone()
                 } else if roll == 3 {
-                    sound_assets.walk3.clone()
-                } else if roll == 4 {
-                    sound_assets.walk4.clone()
-                } else if roll == 5 {
-                    sound_assets.walk5.clone()
-                } else if roll == 6 {
-                    sound_assets.walk6.clone()
-                } else if roll == 7 {
-                    sound_assets.walk7.clone()
-                } else if roll == 8 {
-                    sound_assets.walk8.clone()
-                } else if roll == 9 {
-                    sound_assets.walk9.clone()
+                    sound_assets.swoosh3.clone()
                 } else {
-                    sound_assets.walk10.clone()
-                };
-
-                let vol = if sfx_music_volume.sfx { 1.0 } else { 0.0 };
-
-                commands.spawn((
-                    PlaybackSettings::DESPAWN.with_volume(audio::Volume::Linear(vol)),
-                    AudioPlayer(sfx),
-                ));
-            }
-            if dino.timer.just_finished() {
-                if let Some(atlas) = sprite.texture_atlas.as_mut() {
-                    let index = atlas.index.clamp(0, 11);
-                    if index == 11 {
-                        atlas.index = 0;
-                    } else {
-                        atlas.index = index + 1;
-                    }
+                    sound_assets.swoosh4.clone()
                 };
+                (sfx, 0.5)
             }
-        } else {
-            // Idle state
-            if dino.timer.just_finished() {
-                if let Some(atlas) = sprite.texture_atlas.as_mut() {
-                    // First check if atlas.index is between 12-17
-                    if atlas.index < 12 || atlas.index > 17 {
-                        atlas.index = 12;
-                    }
+            _ => continue,
+        };
 
-                    let index = atlas.index.clamp(12, 17);
-
-                    // Handle idle animation frames, with a random chance to loop back to start
-                    // to make it less repetitive.
-                    if index == 17 {
-                        // Gen a 1 in 10 change to reset to 12
-                        dino.idle_frame_forward = false;
-                        let roll: u8 = rng.random_range(0..40);
-                        if roll == 0 {
-                            atlas.index = 16;
-                        }
-                    } else if index == 16 {
-                        if dino.idle_frame_forward {
-                            atlas.index = 17;
-                        } else {
-                            dino.idle_frame_forward = true;
-                            atlas.index = 12;
-                        }
-                    } else {
-                        let roll: u8 = rng.random_range(0..10);
-                        if roll == 0 {
-                            atlas.index = index + 1;
-                        }
-                    }
-                };
-            }
-        }
+        let vol = if sfx_music_volume.sfx { vol } else { 0.0 };
+        commands.spawn((
+            PlaybackSettings::DESPAWN.with_volume(bevy::audio::Volume::Linear(vol)),
+            AudioPlayer(sfx),
+        ));
     }
 }
 
@@ -1288,25 +1521,42 @@ pub fn arrow_move(
 fn apple_collect(
     mut commands: Commands,
     mut apple_basket: ResMut<AppleBasket>,
-    apples: Query<(Entity, &Apple)>,
-    dino_query: Query<&Dino>,
+    apples: Query<(Entity, &Apple, Option<&TileCollectible>)>,
+    mut generated_tiles: ResMut<GeneratedTiles>,
+    grid: Res<SpatialGrid>,
+    mut dino_query: Query<&mut Dino>,
     sound_assets: Res<SoundAssets>,
     sfx_music_volume: Res<SfxMusicVolume>,
 ) {
-    let Ok(dino) = dino_query.single() else {
-        return;
-    };
-    for (entity, apple) in apples {
-        if apple.aabb.intersects(&dino.aabb) {
-            let vol = if sfx_music_volume.sfx { 2.5 } else { 0.0 };
+    // Both dinos can touch the same apple, the first one gets it
+    let mut collected = Vec::new();
+    for mut dino in dino_query.iter_mut() {
+        for (entity, apple, slot) in grid
+            .query(&dino.aabb)
+            .into_iter()
+            .filter_map(|entity| apples.get(entity).ok())
+        {
+            if collected.contains(&entity) {
+                continue;
+            }
+            if apple.aabb.intersects(&dino.aabb) {
+                let vol = if sfx_music_volume.sfx { 2.5 } else { 0.0 };
 
-            commands.spawn((
-                PlaybackSettings::DESPAWN.with_volume(bevy::audio::Volume::Linear(vol)),
-                AudioPlayer(sound_assets.collect_sfx.clone()),
-            ));
-            apple_basket.0 += 1;
-            // Do an animation
-            commands.entity(entity).despawn();
+                commands.spawn((
+                    PlaybackSettings::DESPAWN.with_volume(bevy::audio::Volume::Linear(vol)),
+                    AudioPlayer(sound_assets.collect_sfx.clone()),
+                ));
+                apple_basket.0 += 1;
+                dino.apples += 1;
+                if let Some(slot) = slot
+                    && let Some(tile) = generated_tiles.0.get_mut(&slot.tile)
+                {
+                    tile.trees[slot.index].collectible = None;
+                }
+                // Do an animation
+                commands.entity(entity).despawn();
+                collected.push(entity);
+            }
         }
     }
 }
@@ -1315,85 +1565,104 @@ fn apple_collect(
 fn clock_collect(
     mut commands: Commands,
     mut game_timer: ResMut<GameTimer>,
-    clocks: Query<(Entity, &TimeExtender)>,
+    ruleset: Res<Ruleset>,
+    clocks: Query<(Entity, &TimeExtender, Option<&TileCollectible>)>,
+    mut generated_tiles: ResMut<GeneratedTiles>,
+    grid: Res<SpatialGrid>,
     dino_query: Query<&Dino>,
     sound_assets: Res<SoundAssets>,
     sfx_music_volume: Res<SfxMusicVolume>,
 ) {
-    let Ok(dino) = dino_query.single() else {
-        return;
-    };
-    for (entity, clock) in clocks {
-        if clock.aabb.intersects(&dino.aabb) {
-            let vol = if sfx_music_volume.sfx { 2.5 } else { 0.0 };
+    let mut collected = Vec::new();
+    for dino in dino_query.iter() {
+        for (entity, clock, slot) in grid
+            .query(&dino.aabb)
+            .into_iter()
+            .filter_map(|entity| clocks.get(entity).ok())
+        {
+            if collected.contains(&entity) {
+                continue;
+            }
+            if clock.aabb.intersects(&dino.aabb) {
+                let vol = if sfx_music_volume.sfx { 2.5 } else { 0.0 };
 
-            commands.spawn((
-                PlaybackSettings::DESPAWN.with_volume(bevy::audio::Volume::Linear(vol)),
-                AudioPlayer(sound_assets.collect_sfx.clone()),
-            ));
-            let remaining = game_timer.0.remaining().as_secs_f32();
-            game_timer.0 = Timer::from_seconds(remaining + 60., TimerMode::Once);
-            // Do an animation
-            commands.entity(entity).despawn();
+                commands.spawn((
+                    PlaybackSettings::DESPAWN.with_volume(bevy::audio::Volume::Linear(vol)),
+                    AudioPlayer(sound_assets.collect_sfx.clone()),
+                ));
+                let remaining = game_timer.0.remaining().as_secs_f32();
+                game_timer.0 =
+                    Timer::from_seconds(remaining + ruleset.clock_bonus, TimerMode::Once);
+                if let Some(slot) = slot
+                    && let Some(tile) = generated_tiles.0.get_mut(&slot.tile)
+                {
+                    tile.platforms[slot.index].collectible = None;
+                }
+                // Do an animation
+                commands.entity(entity).despawn();
+                collected.push(entity);
+            }
         }
     }
 }
 
 fn update_scoreboard(
-    mut scoreboard: Query<&mut Text, With<Scoreboard>>,
-    apple_basket: Res<AppleBasket>,
+    mut scoreboard: Query<(&mut Text, &PlayerHud), With<Scoreboard>>,
+    dino_query: Query<(&Dino, &Player)>,
 ) {
-    let Ok(mut scoreboard_text) = scoreboard.single_mut() else {
-        return;
-    };
-
-    scoreboard_text.0 = apple_basket.0.to_string();
+    for (mut scoreboard_text, hud) in scoreboard.iter_mut() {
+        if let Some((dino, _)) = dino_query.iter().find(|(_, player)| player.0 == hud.0) {
+            scoreboard_text.0 = dino.apples.to_string();
+        }
+    }
 }
 
 fn update_healthboard(
     mut commands: Commands,
-    health_icons: Query<(Entity, &HealthBar)>,
-    dino_query: Query<&Dino>,
+    health_icons: Query<(Entity, &HealthBar, &PlayerHud)>,
+    dino_query: Query<(&Dino, &Player)>,
+    players: Res<Players>,
+    mut winner: ResMut<Winner>,
     mut game_status: ResMut<GameStatus>,
     mut game_state: ResMut<NextState<GameState>>,
 ) {
-    let Ok(dino) = dino_query.single() else {
-        return;
-    };
-
-    for (entity, dino_health_icon) in health_icons {
-        if dino.health == 80 {
-            if dino_health_icon.0 >= 4 {
+    for (dino, player) in dino_query.iter() {
+        for (entity, dino_health_icon, _) in
+            health_icons.iter().filter(|(_, _, hud)| hud.0 == player.0)
+        {
+            if dino.health == 80 && dino_health_icon.0 >= 4 {
                 commands.entity(entity).despawn();
             }
-        }
-        if dino.health == 60 {
-            if dino_health_icon.0 >= 3 {
+            if dino.health == 60 && dino_health_icon.0 >= 3 {
                 commands.entity(entity).despawn();
             }
-        }
-        if dino.health == 40 {
-            if dino_health_icon.0 >= 2 {
+            if dino.health == 40 && dino_health_icon.0 >= 2 {
                 commands.entity(entity).despawn();
             }
-        }
-        if dino.health == 20 {
-            if dino_health_icon.0 >= 1 {
+            if dino.health == 20 && dino_health_icon.0 >= 1 {
                 commands.entity(entity).despawn();
             }
-        }
-        if dino.health == 0 {
-            if dino_health_icon.0 == 0 {
+            if dino.health == 0 && dino_health_icon.0 == 0 {
                 commands.entity(entity).despawn();
             }
         }
     }
 
-    if dino.health <= 0 {
-        *game_status = GameStatus::Lose;
-        game_state.set(GameState::NotRunning);
-        commands.send_event(SceneChange(AppState::GameOver));
+    if dino_query.iter().all(|(dino, _)| dino.health > 0) {
+        return;
+    }
+
+    // In versus the dino still standing wins, everywhere else one down is everyone down
+    let survivor = dino_query.iter().find(|(dino, _)| dino.health > 0);
+    match survivor {
+        Some((_, player)) if *players == Players::Versus => {
+            winner.0 = Some(player.0);
+            *game_status = GameStatus::Win;
+        }
+        _ => *game_status = GameStatus::Lose,
     }
+    game_state.set(GameState::NotRunning);
+    commands.send_event(SceneChange(AppState::GameOver));
 }
 
 fn update_timeboard(
@@ -1403,7 +1672,19 @@ fn update_timeboard(
     mut timeboard: Query<&mut Text, With<Timeboard>>,
     mut game_status: ResMut<GameStatus>,
     mut game_state: ResMut<NextState<GameState>>,
+    game_mode: Res<GameMode>,
+    run_time: Res<RunTime>,
 ) {
+    if !game_mode.has_time_limit() {
+        if let Ok(mut timeboard_text) = timeboard.single_mut() {
+            timeboard_text.0 = match *game_mode {
+                GameMode::TimeAttack => run_time.0.elapsed_secs().floor().to_string(),
+                _ => "--".into(),
+            };
+        }
+        return;
+    }
+
     game_timer.0.tick(time.delta());
     let Ok(mut timeboard_text) = timeboard.single_mut() else {
         return;
@@ -1421,24 +1702,40 @@ fn update_heightboard(
     mut commands: Commands,
 
     target_height: Res<TargetHeight>,
-    dino: Query<&Transform, With<Dino>>,
+    dino: Query<(&Transform, &Player), With<Dino>>,
     mut height_board: Query<&mut Text, With<Heightboard>>,
     mut game_status: ResMut<GameStatus>,
     mut game_state: ResMut<NextState<GameState>>,
+    game_mode: Res<GameMode>,
+    mut max_altitude: ResMut<MaxAltitude>,
+    mut winner: ResMut<Winner>,
 ) {
     let Ok(mut heightboard_text) = height_board.single_mut() else {
         return;
     };
 
-    let Ok(transform) = dino.single() else {
+    // The board follows whoever is in the lead
+    let Some((transform, leader)) = dino
+        .iter()
+        .max_by(|a, b| a.0.translation.y.total_cmp(&b.0.translation.y))
+    else {
         return;
     };
 
+    let altitude = Dino::altitude(transform.translation.y);
+    max_altitude.0 = max_altitude.0.max(altitude);
+
+    if !game_mode.has_target() {
+        heightboard_text.0 = altitude.max(0.0).ceil().to_string();
+        return;
+    }
+
     heightboard_text.0 = (target_height.0 - transform.translation.y)
         .ceil()
         .to_string();
 
     if target_height.0 - transform.translation.y <= 0.0 {
+        winner.0 = Some(leader.0);
         *game_status = GameStatus::Win;
         game_state.set(GameState::NotRunning);
         commands.send_event(SceneChange(AppState::GameOver));
@@ -1450,13 +1747,15 @@ fn game_over(
     mut commands: Commands,
     mut pending_scene_change: ResMut<PendingSceneChange>,
     assets: Res<ImageAssets>,
+    data_assets: Res<DataAssets>,
 ) {
     for event in reader.read() {
         let data = event.0.clone();
         pending_scene_change.0 = Some(data);
         commands.spawn((
             // BackgroundColor(BLACK.into()),
-            Transition::new(13, 6),
+            Transition::new(6),
+            Animator::new(data_assets.transition_clips.clone(), "close"),
             Node {
                 width: Val::Percent(100.),
                 height: Val::Percent(100.),
@@ -1520,10 +1819,9 @@ fn fade_in_music(
 
 fn scene_transition(
     mut commands: Commands,
-    time: Res<Time>,
     pending_scene_change: Res<PendingSceneChange>,
     mut loading_state: ResMut<NextState<AppState>>,
-    mut transition_ui: Query<(Entity, &mut ImageNode, &mut Transition)>,
+    mut transition_ui: Query<(Entity, &ImageNode, &Animator, &mut Transition)>,
     mut game_music: Query<Entity, (With<GameMusic>, Without<FadeOutMusic>)>,
     menu_music: Query<Entity, (With<WaitingMusic>, Without<FadeOutMusic>)>,
 ) {
@@ -1531,40 +1829,41 @@ fn scene_transition(
         return;
     };
 
-    if *next_scene == AppState::GameOver {
+    if *next_scene == AppState::GameOver || *next_scene == AppState::Menu {
         if let Ok(entity) = game_music.single_mut() {
             commands
                 .entity(entity)
                 .insert(FadeOutMusic)
                 .remove::<MusicVolume>();
         }
-    } else if *next_scene == AppState::Game {
-        if let Ok(entity) = menu_music.single() {
-            commands
-                .entity(entity)
-                .insert(FadeOutMusic)
-                .remove::<MusicVolume>();
-        }
+    } else if *next_scene == AppState::Game
+        && let Ok(entity) = menu_music.single()
+    {
+        commands
+            .entity(entity)
+            .insert(FadeOutMusic)
+            .remove::<MusicVolume>();
     }
 
-    for (entity, mut sprite, mut transition) in transition_ui.iter_mut() {
-        transition.timer.tick(time.delta());
+    for (entity, image, animator, mut transition) in transition_ui.iter_mut() {
+        let Some(atlas) = image.texture_atlas.as_ref() else {
+            continue;
+        };
 
-        if transition.timer.just_finished() {
-            if let Some(atlas) = sprite.texture_atlas.as_mut() {
-                if atlas.index == transition.pause_frame - 1 {
-                    atlas.index += 1;
-                    loading_state.set(next_scene.clone());
-                } else if atlas.index < transition.last_frame {
-                    atlas.index += 1;
-                } else {
-                    commands.entity(entity).despawn();
-                }
-            }
+        if !transition.covered && atlas.index >= transition.pause_frame {
+            transition.covered = true;
+            loading_state.set(next_scene.clone());
+        }
+        if animator.finished() {
+            commands.entity(entity).despawn();
         }
     }
 }
 
+fn restart_game(mut app_state: ResMut<NextState<AppState>>) {
+    app_state.set(AppState::Game);
+}
+
 #[derive(Component)]
 pub struct WaitingMusic;
 
@@ -1596,6 +1895,16 @@ fn game_over_scoreboard(
     game_over_options: Res<Assets<GameOverLex>>,
     sound_assets: Res<SoundAssets>,
     sfx_music_volume: Res<SfxMusicVolume>,
+    run_seed: Res<RunSeed>,
+    game_mode: Res<GameMode>,
+    run_time: Res<RunTime>,
+    max_altitude: Res<MaxAltitude>,
+    (players, winner, selected_level, chosen_seed): (
+        Res<Players>,
+        Res<Winner>,
+        Res<SelectedLevel>,
+        Res<ChosenSeed>,
+    ),
 ) {
     let lex = if game_status.won() {
         let vol = if sfx_music_volume.sfx { 0.5 } else { 0.0 };
@@ -1604,7 +1913,11 @@ fn game_over_scoreboard(
             PlaybackSettings::DESPAWN.with_volume(bevy::audio::Volume::Linear(vol)),
             AudioPlayer(sound_assets.win.clone()),
         ));
-        get_lex_by_id(&game_over_options, "win")
+        match *players {
+            Players::Single => get_lex_by_id(&game_over_options, "win"),
+            Players::Coop => get_lex_by_id(&game_over_options, "coop_win"),
+            Players::Versus => get_lex_by_id(&game_over_options, "winner"),
+        }
     } else if game_status.lost() {
         let vol = if sfx_music_volume.sfx { 0.8 } else { 0.0 };
         commands.spawn((
@@ -1616,10 +1929,39 @@ fn game_over_scoreboard(
         return;
     };
 
-    let display_text = lex.lex.from_language(&language.0);
-
-    let apples = apple_basket.0;
-    let time_left = game_timer.0.remaining_secs().ceil();
+    let mut display_text = lex.lex.in_language(&language.0);
+    if let Some(winner) = winner.0.filter(|_| *players == Players::Versus) {
+        display_text = format!("{} {}", display_text, winner + 1);
+    }
+    // Endless runs always end by running out of time or health, but still score
+    let win_text = get_lex_by_id(&game_over_options, "win")
+        .lex
+        .in_language(&language.0);
+    let seed_text = format!(
+        "{} {}",
+        get_lex_by_id(&game_over_options, "seed")
+            .lex
+            .in_language(&language.0),
+        run_seed.0
+    );
+    let replay_text = get_lex_by_id(&game_over_options, "replay")
+        .lex
+        .in_language(&language.0);
+
+    let score = game_mode.leaderboard_score(
+        &RunSummary {
+            won: game_status.won(),
+            apples: apple_basket.0,
+            time_left: game_timer.0.remaining_secs().ceil(),
+            elapsed_secs: run_time.0.elapsed_secs(),
+            max_altitude: max_altitude.0,
+        },
+        &RunOrigin {
+            players: *players,
+            level: selected_level.0.is_some(),
+            seeded: chosen_seed.0,
+        },
+    );
 
     commands.entity(hud.0).with_children(|parent| {
         parent
@@ -1636,46 +1978,20 @@ fn game_over_scoreboard(
                 },
             ))
             .with_children(|p| {
-                let apple_total = apples * 12;
-                let time_total = time_left as u32 * 2;
-                let cider_total = (apples / 10) * 500;
-                let total = apple_total + time_total + cider_total;
-
-                // Math
-                // Apples = x12
-                // Time = x2
-                // Every 10th apple = Cider
-                // Cider = 500 pts
-
-                if game_status.won() {
-                    p.spawn((
-                        TextFont::from_font(BODY_FONT)
-                            .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 25.),
-                        Text(format!("Total Apples: {} x 12 = {}", apples, apple_total)),
-                    ));
-                    p.spawn((
-                        TextFont::from_font(BODY_FONT)
-                            .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 25.),
-                        Text(format!(
-                            "Total Cider: {} x 500 = {}",
-                            apples / 10,
-                            cider_total
-                        )),
-                    ));
-                    p.spawn((
-                        TextFont::from_font(BODY_FONT)
-                            .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 25.),
-                        Text(format!(
-                            "Time Remaining: {} x 2 = {}",
-                            time_left, time_total
-                        )),
-                    ));
-                    total_points.0 = total;
+                if let Some(score) = score {
+                    for line in score.lines {
+                        p.spawn((
+                            TextFont::from_font(BODY_FONT)
+                                .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 25.),
+                            Text(line),
+                        ));
+                    }
+                    total_points.0 = score.total;
                     p.spawn(spacer());
                     p.spawn((
                         TextFont::from_font(BODY_FONT)
                             .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 25.),
-                        Text(display_text + " " + &total.to_string()),
+                        Text(win_text + " " + &score.display),
                     ));
                     p.spawn(spacer());
                     p.spawn((
@@ -1698,9 +2014,15 @@ fn game_over_scoreboard(
                     p.spawn((button(
                         get_lex_by_id(&game_over_options, "submit")
                             .lex
-                            .from_language(&language.0),
+                            .in_language(&language.0),
                     ),))
                         .observe(submit_high_score);
+                    p.spawn(button(replay_text)).observe(replay_seed);
+                    p.spawn((
+                        TextFont::from_font(BODY_FONT)
+                            .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 25.),
+                        Text(seed_text),
+                    ));
                 } else {
                     total_points.0 = 0;
                     p.spawn((
@@ -1709,8 +2031,14 @@ fn game_over_scoreboard(
                         Text(display_text),
                     ));
                     p.spawn(spacer());
-                    p.spawn(button("Continue".into()))
-                        .observe(submit_high_score);
+                    p.spawn(button("Continue".into())).observe(show_high_scores);
+                    p.spawn(button(replay_text)).observe(replay_seed);
+                    p.spawn(spacer());
+                    p.spawn((
+                        TextFont::from_font(BODY_FONT)
+                            .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 25.),
+                        Text(seed_text),
+                    ));
                 }
             });
     });
@@ -1727,7 +2055,11 @@ const NORMAL_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
 const HOVERED_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
 const PRESSED_BUTTON: Color = Color::srgb(0.35, 0.75, 0.35);
 
-fn button(text: String) -> impl Bundle + use<> {
+pub fn button(text: String) -> impl Bundle + use<> {
+    button_sized(text, 150.0, 65.0)
+}
+
+pub fn button_sized(text: String, width: f32, height: f32) -> impl Bundle + use<> {
     (
         Node {
             width: Val::Percent(100.0),
@@ -1739,8 +2071,8 @@ fn button(text: String) -> impl Bundle + use<> {
         children![(
             Button,
             Node {
-                width: Val::Px(150.0),
-                height: Val::Px(65.0),
+                width: Val::Px(width),
+                height: Val::Px(height),
                 border: UiRect::all(Val::Px(5.0)),
                 // horizontally center child text
                 justify_content: JustifyContent::Center,
@@ -1795,6 +2127,28 @@ pub fn submit_high_score(_: Trigger<Pointer<Click>>, mut commands: Commands) {
     commands.send_event(PostHighScore);
 }
 
+/// Runs that didn't score go straight to their mode's leaderboard without posting anything
+pub fn show_high_scores(
+    _: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    game_mode: Res<GameMode>,
+    mut leaderboard_mode: ResMut<LeaderboardMode>,
+) {
+    leaderboard_mode.0 = *game_mode;
+    commands.send_event(FetchHighScores);
+    commands.send_event(SceneChange(AppState::HighScores));
+}
+
+pub fn replay_seed(
+    _: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    run_seed: Res<RunSeed>,
+    mut requested_seed: ResMut<RequestedSeed>,
+) {
+    requested_seed.0 = Some(run_seed.0);
+    commands.send_event(SceneChange(AppState::Game));
+}
+
 pub fn go_to_menu(_: Trigger<Pointer<Click>>, mut commands: Commands) {
     commands.send_event(SceneChange(AppState::Menu));
 }
@@ -1807,8 +2161,11 @@ pub fn post_high_score(
     mut ev_request: EventWriter<HttpRequest>,
     text_input_query: Query<&TextInputValue>,
     total_points: Res<TotalPoints>,
+    game_mode: Res<GameMode>,
+    mut leaderboard_mode: ResMut<LeaderboardMode>,
 ) {
     info!("posting high score");
+    leaderboard_mode.0 = *game_mode;
     let name = match text_input_query.single() {
         Ok(t) => t.0.clone(),
         Err(_) => String::new(),
@@ -1818,7 +2175,11 @@ pub fn post_high_score(
     let client = HttpClient::new();
     match client
         .post(LEADERBOARD_URL)
-        .json(&serde_json::json!({"name": name, "score": score}))
+        .json(&serde_json::json!({
+            "name": name,
+            "score": score,
+            "category": game_mode.category(),
+        }))
         .try_build()
     {
         Ok(request) => {
@@ -1830,6 +2191,31 @@ pub fn post_high_score(
     commands.send_event(SceneChange(AppState::HighScores));
 }
 
+#[derive(Event)]
+pub struct FetchHighScores;
+
+/// Asks for the shown leaderboard without posting a score
+pub fn fetch_high_scores(
+    mut ev_request: EventWriter<HttpRequest>,
+    leaderboard_mode: Res<LeaderboardMode>,
+) {
+    info!("fetching high scores");
+    let client = HttpClient::new();
+    match client
+        .get(format!(
+            "{}?category={}",
+            LEADERBOARD_URL,
+            leaderboard_mode.0.category()
+        ))
+        .try_build()
+    {
+        Ok(request) => {
+            ev_request.write(request);
+        }
+        Err(e) => error!(?e),
+    }
+}
+
 fn handle_response(
     mut ev_resp: EventReader<HttpResponse>,
     mut high_score_data: ResMut<HighScores>,
@@ -1863,6 +2249,28 @@ fn setup_high_score_board(mut commands: Commands, hud: Res<Hud>) {
                         .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 25.),
                     Text("   High Scores\n----------------\n".into()),
                 ));
+                p.spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(10.0),
+                    ..default()
+                })
+                .with_children(|row| {
+                    for mode in GameMode::ALL {
+                        row.spawn((
+                            LeaderboardTab(mode),
+                            Node {
+                                width: Val::Px(160.0),
+                                height: Val::Px(55.0),
+                                ..default()
+                            },
+                        ))
+                        .with_children(|tab| {
+                            tab.spawn(button_sized(mode.name().into(), 160.0, 55.0));
+                        })
+                        .observe(pick_leaderboard);
+                    }
+                });
+                p.spawn(spacer());
                 p.spawn((
                     HighScoreboard,
                     TextFont::from_font(BODY_FONT)
@@ -1881,6 +2289,8 @@ fn setup_high_score_board(mut commands: Commands, hud: Res<Hud>) {
 
                     ..default()
                 },
+                // Only the button goes back, the rest of the area lets clicks reach the tabs
+                Pickable::IGNORE,
             ))
             .with_children(|p| {
                 p.spawn(button("Menu".into()));
@@ -1889,6 +2299,19 @@ fn setup_high_score_board(mut commands: Commands, hud: Res<Hud>) {
     });
 }
 
+fn pick_leaderboard(
+    trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    tabs: Query<&LeaderboardTab>,
+    mut leaderboard_mode: ResMut<LeaderboardMode>,
+) {
+    let Ok(tab) = tabs.get(trigger.target()) else {
+        return;
+    };
+    leaderboard_mode.0 = tab.0;
+    commands.send_event(FetchHighScores);
+}
+
 fn setup_credits(mut commands: Commands, hud: Res<Hud>) {
     commands.entity(hud.0).with_children(|parent| {
         parent
@@ -1951,29 +2374,57 @@ fn setup_credits(mut commands: Commands, hud: Res<Hud>) {
 
 fn update_high_scoreboard(
     high_score_data: Res<HighScores>,
+    leaderboard_mode: Res<LeaderboardMode>,
     mut high_scoreboard: Query<&mut Text, With<HighScoreboard>>,
 ) {
     let Ok(mut text) = high_scoreboard.single_mut() else {
         return;
     };
 
-    let mut leaders = high_score_data.0.clone();
-    leaders.sort_by(|a, b| b.score.cmp(&a.score));
+    let game_mode = leaderboard_mode.0;
+    let mut leaders = high_score_data
+        .0
+        .iter()
+        .filter(|data| data.category == game_mode.category())
+        .cloned()
+        .collect::<Vec<_>>();
+    if game_mode.lower_score_wins() {
+        // Older builds posted unfinished runs as 0
+        leaders.retain(|data| data.score > 0);
+        leaders.sort_by_key(|data| data.score);
+    } else {
+        leaders.sort_by_key(|data| std::cmp::Reverse(data.score));
+    }
 
     let display_data = leaders
         .iter()
         .enumerate()
         .filter(|(idx, _data)| *idx < 10)
-        .map(|(idx, data)| format!("#{} - {}: {}", idx + 1, data.name, data.score))
+        .map(|(idx, data)| {
+            format!(
+                "#{} - {}: {}",
+                idx + 1,
+                data.name,
+                game_mode.format_score(data.score)
+            )
+        })
         .collect::<Vec<_>>()
         .join("\n\n");
 
-    text.0 = display_data;
+    text.0 = format!("{}\n\n{}", game_mode.name(), display_data);
 }
 
 #[derive(Component)]
 pub struct HighScoreboard;
 
+/// Switches the high score screen to this mode's leaderboard
+#[derive(Component)]
+pub struct LeaderboardTab(pub GameMode);
+
+/// Leaderboard the high score screen shows. Posting a score shows the run's own mode.
+#[derive(Resource, Default)]
+pub struct LeaderboardMode(pub GameMode);
+
 #[derive(Event)]
 pub struct RenderHighScores;
 
@@ -1983,7 +2434,7 @@ fn handle_error(mut ev_error: EventReader<HttpResponseError>) {
     }
 }
 
-fn get_lex_by_id(assets: &Assets<GameOverLex>, id: &str) -> GameOverLex {
+pub fn get_lex_by_id(assets: &Assets<GameOverLex>, id: &str) -> GameOverLex {
     assets
         .iter()
         .find(|(_, data)| data.id == id)
@@ -2000,6 +2451,13 @@ pub struct LeaderboardOutput {
 pub struct HighScoreData {
     name: String,
     score: u32,
+    #[serde(default = "classic_category")]
+    category: String,
+}
+
+/// Scores posted before there were modes were all Classic runs
+fn classic_category() -> String {
+    GameMode::Classic.category().into()
 }
 
 #[derive(Resource, Default, Debug)]
@@ -0,0 +1,586 @@
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(GamepadDeadZones::default())
+        .insert_resource(InputBindings::load())
+        .insert_resource(Rebinding::default())
+        .init_resource::<ButtonInput<Action>>()
+        .insert_resource(ActionStrength::default())
+        .insert_resource(VirtualButtons::default())
+        .add_systems(
+            PreUpdate,
+            (update_actions, update_player_actions, capture_rebinding)
+                .chain()
+                .after(InputSystem),
+        )
+        .add_systems(
+            Update,
+            save_bindings.run_if(
+                resource_changed::<InputBindings>.and(not(resource_added::<InputBindings>)),
+            ),
+        );
+}
+
+/// Everything the player can ask for. Gameplay and menus read these through
+/// `ButtonInput<Action>` and `ActionStrength`, never the devices directly.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Jump,
+    Left,
+    Right,
+    Up,
+    Down,
+    Attack,
+    Confirm,
+    Back,
+    Pause,
+    Restart,
+    /// Dev builds only, ends the run as a win
+    DebugWin,
+    /// Dev builds only, ends the run as a loss
+    DebugLose,
+}
+
+impl Action {
+    /// Actions the player can rebind from the controls page
+    pub const REBINDABLE: [Action; 5] = [
+        Action::Jump,
+        Action::Left,
+        Action::Right,
+        Action::Attack,
+        Action::Pause,
+    ];
+}
+
+/// One physical input that triggers an action.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Binding {
+    Key(KeyCode),
+    Button(GamepadButton),
+    Mouse(MouseButton),
+    /// A gamepad stick pushed past the dead zone, `positive` picks the direction
+    Axis {
+        axis: GamepadAxis,
+        positive: bool,
+    },
+}
+
+impl Binding {
+    fn pressed(&self, devices: &Devices, dead_zones: &GamepadDeadZones) -> bool {
+        match *self {
+            Self::Key(key) => devices.keyboard.pressed(key),
+            Self::Mouse(button) => devices.mouse.pressed(button),
+            Self::Button(button) => devices.gamepads.iter().any(|g| g.pressed(button)),
+            Self::Axis { axis, positive } => {
+                axis_value(devices, axis, positive, dead_zones.press) > 0.0
+            }
+        }
+    }
+
+    /// How far the input is pushed, 1.0 for anything that isn't analog.
+    fn strength(&self, devices: &Devices, dead_zones: &GamepadDeadZones) -> f32 {
+        match *self {
+            Self::Axis { axis, positive } => {
+                axis_value(devices, axis, positive, dead_zones.movement)
+            }
+            _ if self.pressed(devices, dead_zones) => 1.0,
+            _ => 0.0,
+        }
+    }
+
+    /// Same kind of device, so a rebind replaces the key with a key and a button with a button
+    fn same_device(&self, other: &Binding) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    /// Short name for the controls page, e.g. "Space" or "South"
+    pub fn label(&self) -> String {
+        match self {
+            Self::Key(key) => format!("{:?}", key),
+            Self::Button(button) => format!("{:?}", button),
+            Self::Mouse(button) => format!("Mouse {:?}", button),
+            Self::Axis { axis, positive } => {
+                format!("{:?} {}", axis, if *positive { "+" } else { "-" })
+            }
+        }
+    }
+}
+
+/// Furthest any gamepad pushes `axis` in one direction, 0.0 for the other direction.
+fn axis_value(devices: &Devices, axis: GamepadAxis, positive: bool, dead_zone: f32) -> f32 {
+    devices
+        .gamepads
+        .iter()
+        .filter_map(|gamepad| gamepad.get(axis))
+        .map(|value| {
+            let value = apply_dead_zone(value, dead_zone);
+            if positive { value } else { -value }
+        })
+        .fold(0.0, f32::max)
+}
+
+/// The devices bindings are read from, gathered once per frame.
+struct Devices<'a> {
+    keyboard: &'a ButtonInput<KeyCode>,
+    mouse: &'a ButtonInput<MouseButton>,
+    gamepads: Vec<&'a Gamepad>,
+}
+
+/// Which inputs trigger each action. Saved whenever it changes and loaded on start, so
+/// rebinds survive a reload.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InputBindings(pub HashMap<Action, Vec<Binding>>);
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        use Binding::*;
+
+        Self(HashMap::from([
+            (
+                Action::Jump,
+                vec![Key(KeyCode::Space), Button(GamepadButton::South)],
+            ),
+            (
+                Action::Left,
+                vec![
+                    Key(KeyCode::ArrowLeft),
+                    Key(KeyCode::KeyA),
+                    Button(GamepadButton::DPadLeft),
+                    Axis {
+                        axis: GamepadAxis::LeftStickX,
+                        positive: false,
+                    },
+                ],
+            ),
+            (
+                Action::Right,
+                vec![
+                    Key(KeyCode::ArrowRight),
+                    Key(KeyCode::KeyD),
+                    Button(GamepadButton::DPadRight),
+                    Axis {
+                        axis: GamepadAxis::LeftStickX,
+                        positive: true,
+                    },
+                ],
+            ),
+            (
+                Action::Up,
+                vec![
+                    Key(KeyCode::ArrowUp),
+                    Key(KeyCode::KeyW),
+                    Button(GamepadButton::DPadUp),
+                    Axis {
+                        axis: GamepadAxis::LeftStickY,
+                        positive: true,
+                    },
+                ],
+            ),
+            (
+                Action::Down,
+                vec![
+                    Key(KeyCode::ArrowDown),
+                    Key(KeyCode::KeyS),
+                    Button(GamepadButton::DPadDown),
+                    Axis {
+                        axis: GamepadAxis::LeftStickY,
+                        positive: false,
+                    },
+                ],
+            ),
+            (
+                Action::Attack,
+                vec![
+                    Key(KeyCode::KeyJ),
+                    Button(GamepadButton::West),
+                    Button(GamepadButton::RightTrigger),
+                ],
+            ),
+            (
+                Action::Confirm,
+                vec![
+                    Key(KeyCode::Enter),
+                    Key(KeyCode::NumpadEnter),
+                    Button(GamepadButton::South),
+                ],
+            ),
+            (
+                Action::Back,
+                vec![
+                    Key(KeyCode::Escape),
+                    Button(GamepadButton::East),
+                    Mouse(MouseButton::Back),
+                ],
+            ),
+            (
+                Action::Pause,
+                vec![Key(KeyCode::Escape), Button(GamepadButton::Start)],
+            ),
+            (Action::Restart, vec![Key(KeyCode::F1)]),
+            (Action::DebugWin, vec![Key(KeyCode::KeyZ)]),
+            (Action::DebugLose, vec![Key(KeyCode::KeyX)]),
+        ]))
+    }
+}
+
+const BINDINGS_KEY: &str = "dino-input-bindings";
+
+impl InputBindings {
+    pub fn get(&self, action: Action) -> &[Binding] {
+        self.0.get(&action).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Replaces the action's binding on the same kind of device, or adds one if it had none.
+    pub fn rebind(&mut self, action: Action, binding: Binding) {
+        let bindings = self.0.entry(action).or_default();
+        match bindings.iter_mut().find(|b| b.same_device(&binding)) {
+            Some(existing) => *existing = binding,
+            None => bindings.push(binding),
+        }
+    }
+
+    /// Bindings for one player of a local two player run. The first player gets the left half
+    /// of the keyboard and the second the right half, the gamepad bindings are the same for
+    /// both since each player only reads their own pad.
+    pub fn for_player(index: usize) -> Self {
+        use KeyCode::*;
+
+        let keys: [(Action, &[KeyCode]); 4] = if index == 0 {
+            [
+                (Action::Jump, &[KeyW, Space]),
+                (Action::Left, &[KeyA]),
+                (Action::Right, &[KeyD]),
+                (Action::Attack, &[KeyF]),
+            ]
+        } else {
+            [
+                (Action::Jump, &[ArrowUp]),
+                (Action::Left, &[ArrowLeft]),
+                (Action::Right, &[ArrowRight]),
+                (Action::Attack, &[Slash, ControlRight]),
+            ]
+        };
+
+        let defaults = Self::default();
+        Self(
+            keys.into_iter()
+                .map(|(action, keys)| {
+                    let mut bindings = keys
+                        .iter()
+                        .map(|key| Binding::Key(*key))
+                        .collect::<Vec<_>>();
+                    bindings.extend(defaults.get(action).iter().filter(|binding| {
+                        matches!(binding, Binding::Button(_) | Binding::Axis { .. })
+                    }));
+                    (action, bindings)
+                })
+                .collect(),
+        )
+    }
+
+    /// Saved bindings on top of the defaults, so actions added since the save still work.
+    fn load() -> Self {
+        let mut bindings = Self::default();
+        let Some(saved) = read_saved(BINDINGS_KEY) else {
+            return bindings;
+        };
+
+        match serde_json::from_str::<InputBindings>(&saved) {
+            Ok(saved) => bindings.0.extend(saved.0),
+            Err(error) => warn!(?error, "Ignoring saved input bindings"),
+        }
+        bindings
+    }
+}
+
+fn save_bindings(bindings: Res<InputBindings>) {
+    match serde_json::to_string(&*bindings) {
+        Ok(json) => write_saved(BINDINGS_KEY, &json),
+        Err(error) => warn!(?error, "Could not save input bindings"),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read_saved(key: &str) -> Option<String> {
+    web_sys::window()?
+        .local_storage()
+        .ok()??
+        .get_item(key)
+        .ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write_saved(key: &str, value: &str) {
+    let storage = web_sys::window().and_then(|window| window.local_storage().ok().flatten());
+    if let Some(storage) = storage {
+        if storage.set_item(key, value).is_err() {
+            warn!(key, "Could not write to local storage");
+        }
+    }
+}
+
+/// Where native builds keep `key`, in the usual per-user config folder of the platform.
+#[cfg(not(target_arch = "wasm32"))]
+fn saved_path(key: &str) -> Option<std::path::PathBuf> {
+    use std::env::var_os;
+    use std::path::PathBuf;
+
+    let config = if cfg!(target_os = "windows") {
+        var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    };
+    Some(
+        config?
+            .join(env!("CARGO_PKG_NAME"))
+            .join(format!("{key}.json")),
+    )
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_saved(key: &str) -> Option<String> {
+    std::fs::read_to_string(saved_path(key)?).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_saved(key: &str, value: &str) {
+    let Some(path) = saved_path(key) else {
+        warn!(key, "No config folder to save to");
+        return;
+    };
+    let written = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(&path, value));
+    if let Err(error) = written {
+        warn!(?error, ?path, "Could not save");
+    }
+}
+
+/// How far a stick has to move before it counts, as a fraction of its full range.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct GamepadDeadZones {
+    /// Analog movement, small so the dino can be nudged
+    pub movement: f32,
+    /// A stick counting as a button press, e.g. moving through menus, large so a resting thumb
+    /// doesn't scroll
+    pub press: f32,
+}
+
+impl Default for GamepadDeadZones {
+    fn default() -> Self {
+        Self {
+            movement: 0.075,
+            press: 0.5,
+        }
+    }
+}
+
+/// Zero inside the dead zone, then rescaled so the stick still reaches 1.0 at the rim.
+pub fn apply_dead_zone(value: f32, dead_zone: f32) -> f32 {
+    if value.abs() <= dead_zone {
+        return 0.0;
+    }
+
+    let scaled = (value.abs() - dead_zone) / (1.0 - dead_zone).max(f32::EPSILON);
+    scaled.min(1.0).copysign(value)
+}
+
+/// How hard each action is pushed this frame, from 0.0 to 1.0. Only sticks give values in
+/// between, everything else is 0.0 or 1.0.
+#[derive(Resource, Default, Debug)]
+pub struct ActionStrength(HashMap<Action, f32>);
+
+impl ActionStrength {
+    pub fn get(&self, action: Action) -> f32 {
+        self.0.get(&action).copied().unwrap_or(0.0)
+    }
+
+    /// From -1.0 (left) to 1.0 (right)
+    pub fn move_x(&self) -> f32 {
+        (self.get(Action::Right) - self.get(Action::Left)).clamp(-1.0, 1.0)
+    }
+}
+
+/// Actions held by on-screen controls, which count like any other bound input.
+#[derive(Resource, Default, Debug)]
+pub struct VirtualButtons(pub HashSet<Action>);
+
+pub(crate) fn update_actions(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<&Gamepad>,
+    bindings: Res<InputBindings>,
+    dead_zones: Res<GamepadDeadZones>,
+    virtual_buttons: Res<VirtualButtons>,
+    mut actions: ResMut<ButtonInput<Action>>,
+    mut strength: ResMut<ActionStrength>,
+) {
+    let devices = Devices {
+        keyboard: &keyboard_input,
+        mouse: &mouse_input,
+        gamepads: gamepads.iter().collect(),
+    };
+
+    read_actions(
+        &bindings,
+        &devices,
+        &dead_zones,
+        &virtual_buttons.0,
+        &mut actions,
+        &mut strength,
+    );
+}
+
+/// Actions of one local player in a two player run. Their dino reads these instead of the
+/// shared `ButtonInput<Action>`, which still drives menus and pausing.
+#[derive(Component, Debug)]
+pub struct PlayerInput {
+    pub bindings: InputBindings,
+    /// Which connected gamepad belongs to this player, in the order they were connected
+    pub gamepad: usize,
+    pub actions: ButtonInput<Action>,
+    pub strength: ActionStrength,
+}
+
+impl PlayerInput {
+    pub fn new(index: usize) -> Self {
+        Self {
+            bindings: InputBindings::for_player(index),
+            gamepad: index,
+            actions: ButtonInput::default(),
+            strength: ActionStrength::default(),
+        }
+    }
+}
+
+fn update_player_actions(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<(Entity, &Gamepad)>,
+    dead_zones: Res<GamepadDeadZones>,
+    mut players: Query<&mut PlayerInput>,
+) {
+    let mut connected = gamepads.iter().collect::<Vec<_>>();
+    connected.sort_by_key(|(entity, _)| *entity);
+
+    for mut player in players.iter_mut() {
+        let player = &mut *player;
+        let devices = Devices {
+            keyboard: &keyboard_input,
+            mouse: &mouse_input,
+            gamepads: connected
+                .get(player.gamepad)
+                .map(|(_, gamepad)| *gamepad)
+                .into_iter()
+                .collect(),
+        };
+
+        read_actions(
+            &player.bindings,
+            &devices,
+            &dead_zones,
+            &HashSet::new(),
+            &mut player.actions,
+            &mut player.strength,
+        );
+    }
+}
+
+/// Presses and releases every bound action, `held` counts as pressed whatever the devices say.
+fn read_actions(
+    bindings: &InputBindings,
+    devices: &Devices,
+    dead_zones: &GamepadDeadZones,
+    held: &HashSet<Action>,
+    actions: &mut ButtonInput<Action>,
+    strength: &mut ActionStrength,
+) {
+    actions.clear();
+    strength.0.clear();
+    for (action, action_bindings) in bindings.0.iter() {
+        let held_on_screen = held.contains(action);
+        if held_on_screen
+            || action_bindings
+                .iter()
+                .any(|binding| binding.pressed(devices, dead_zones))
+        {
+            actions.press(*action);
+        } else {
+            actions.release(*action);
+        }
+
+        let value = action_bindings
+            .iter()
+            .map(|binding| binding.strength(devices, dead_zones))
+            .fold(if held_on_screen { 1.0 } else { 0.0 }, f32::max);
+        strength.0.insert(*action, value);
+    }
+}
+
+/// Keys and buttons that stop waiting for a rebind instead of being bound, so backing out
+/// never binds the pause key by accident.
+const CANCEL_REBIND: [Binding; 2] = [
+    Binding::Key(KeyCode::Escape),
+    Binding::Button(GamepadButton::Select),
+];
+
+/// Action waiting for the next key or button press to become its new binding.
+#[derive(Resource, Default, Debug)]
+pub struct Rebinding(pub Option<Action>);
+
+/// Run condition for anything that reacts to actions, none of them should fire while a
+/// rebind is waiting for its key.
+pub fn not_rebinding(rebinding: Res<Rebinding>) -> bool {
+    rebinding.0.is_none()
+}
+
+fn capture_rebinding(
+    mut rebinding: ResMut<Rebinding>,
+    mut bindings: ResMut<InputBindings>,
+    mut actions: ResMut<ButtonInput<Action>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+) {
+    let Some(action) = rebinding.0 else {
+        return;
+    };
+
+    let key = keyboard_input
+        .get_just_pressed()
+        .next()
+        .map(|key| Binding::Key(*key));
+    let button = || {
+        gamepads
+            .iter()
+            .find_map(|gamepad| gamepad.get_just_pressed().next())
+            .map(|button| Binding::Button(*button))
+    };
+    let Some(binding) = key.or_else(button) else {
+        return;
+    };
+
+    if CANCEL_REBIND.contains(&binding) {
+        info!(?action, "Rebinding cancelled");
+    } else {
+        info!(?action, ?binding, "Rebinding");
+        bindings.rebind(action, binding);
+    }
+    rebinding.0 = None;
+
+    // The press that ended the rebind counts as already held, so it doesn't also trigger
+    // whatever it is bound to
+    for (bound_action, action_bindings) in bindings.0.iter() {
+        if action_bindings.contains(&binding) {
+            actions.press(*bound_action);
+            actions.clear_just_pressed(*bound_action);
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use crate::app::{AppState, RESOLUTION_HEIGHT};
+use crate::assets::custom::{DataAssets, ImageAssets};
+use crate::biome::{BiomeArt, BiomeSet};
+use crate::camera::{GameCamera, GameLevelDimensions};
+use crate::game::{
+    self, Apple, Dino, GameState, GameStatus, Obstacle, PhysicalTranslation, Platform, Player,
+    PreviousPhysicalTranslation, SceneChange, TargetHeight, TimeExtender,
+};
+use crate::players::{Players, Winner};
+use crate::ruleset::Ruleset;
+use crate::worldgen;
+use bevy::math::bounding::Aabb2d;
+use bevy::prelude::*;
+use bevy_common_assets::json::JsonAssetPlugin;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins(JsonAssetPlugin::<GameLevel>::new(&["level.json"]))
+        .insert_resource(SelectedLevel::default())
+        .add_systems(OnEnter(AppState::Preload), preload)
+        .add_systems(OnEnter(AppState::Game), setup_level.after(game::setup))
+        .add_systems(OnEnter(AppState::Menu), clear_selected_level)
+        .add_systems(
+            FixedUpdate,
+            fall_out_of_level
+                .after(game::arrow_move)
+                .run_if(in_state(AppState::Game).and(in_state(GameState::Running))),
+        );
+}
+
+fn preload(mut commands: Commands, asset_server: Res<AssetServer>) {
+    info!("Loading levels");
+    commands.insert_resource(LevelCollection::new(
+        &asset_server,
+        vec![
+            "levels/tutorial.level.json",
+            "levels/challenge.level.json",
+            // ...more levels here,
+        ],
+    ));
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct LevelCollection(pub Vec<Handle<GameLevel>>);
+
+impl LevelCollection {
+    fn new(asset_server: &Res<AssetServer>, files: Vec<impl Into<String>>) -> Self {
+        let handles = files
+            .into_iter()
+            .map(|file| asset_server.load(file.into()))
+            .collect::<Vec<_>>();
+
+        Self(handles)
+    }
+
+    pub fn find<'a>(&self, levels: &'a Assets<GameLevel>, id: &str) -> Option<&'a GameLevel> {
+        self.0
+            .iter()
+            .filter_map(|handle| levels.get(handle))
+            .find(|level| level.id == id)
+    }
+}
+
+/// Level picked from the menu. `None` plays the endless procedural climb. Going back to the
+/// menu clears it, while replaying from the game over screen keeps it.
+#[derive(Resource, Default)]
+pub struct SelectedLevel(pub Option<String>);
+
+fn clear_selected_level(mut selected_level: ResMut<SelectedLevel>) {
+    selected_level.0 = None;
+}
+
+/// Run condition for the tile streaming systems, hand-authored levels don't stream tiles.
+pub fn endless(selected_level: Res<SelectedLevel>) -> bool {
+    selected_level.0.is_none()
+}
+
+/// Builds the selected level, or the start of the endless climb when none is selected.
+fn setup_level(
+    mut commands: Commands,
+    assets: Res<ImageAssets>,
+    data: Res<DataAssets>,
+    biome_sets: Res<Assets<BiomeSet>>,
+    collection: Res<LevelCollection>,
+    levels: Res<Assets<GameLevel>>,
+    mut selected_level: ResMut<SelectedLevel>,
+    mut target_height: ResMut<TargetHeight>,
+    ruleset: Res<Ruleset>,
+    players: Res<Players>,
+    mut camera_query: Query<&mut GameCamera>,
+    mut dino_query: Query<(
+        &Player,
+        &mut Transform,
+        &mut PhysicalTranslation,
+        &mut PreviousPhysicalTranslation,
+    )>,
+) {
+    let level = selected_level
+        .0
+        .as_deref()
+        .and_then(|id| collection.find(&levels, id));
+
+    let Some(level) = level else {
+        if let Some(id) = selected_level.0.take() {
+            warn!(level = id, "Level not found, starting the endless climb");
+        }
+        *target_height = TargetHeight(ruleset.target_height);
+        for mut camera in camera_query.iter_mut() {
+            camera.selected_game_level = GameLevelDimensions::endless();
+        }
+        game::spawn_start_platform(&mut commands);
+        return;
+    };
+
+    info!(level = level.id, "Starting level");
+    spawn_level(&mut commands, &assets, biome_sets.get(&data.biomes), level);
+    target_height.0 = level.target_height;
+    for mut camera in camera_query.iter_mut() {
+        camera.selected_game_level = level.camera_bounds.clone();
+    }
+    for (player, mut transform, mut position, mut previous_position) in dino_query.iter_mut() {
+        let start = level.start + players.start_offset(player.0);
+        transform.translation.x = start.x;
+        transform.translation.y = start.y;
+        position.0 = start;
+        previous_position.0 = start;
+    }
+}
+
+/// A dino that drops below the lowest view of the level can never climb back, so the run is
+/// lost, and in versus the other dino wins. The endless climb's bounds are out of reach.
+fn fall_out_of_level(
+    mut commands: Commands,
+    camera_query: Query<&GameCamera>,
+    dino_query: Query<(&Player, &PhysicalTranslation), With<Dino>>,
+    players: Res<Players>,
+    mut winner: ResMut<Winner>,
+    mut game_status: ResMut<GameStatus>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    let Ok(camera) = camera_query.single() else {
+        return;
+    };
+    let kill_plane = camera.selected_game_level.bottom - RESOLUTION_HEIGHT / 2.0;
+    let Some((fallen, _)) = dino_query
+        .iter()
+        .find(|(_, position)| position.0.y < kill_plane)
+    else {
+        return;
+    };
+
+    if *players == Players::Versus {
+        winner.0 = dino_query
+            .iter()
+            .find(|(player, _)| player.0 != fallen.0)
+            .map(|(player, _)| player.0);
+        *game_status = GameStatus::Win;
+    } else {
+        *game_status = GameStatus::Lose;
+    }
+    game_state.set(GameState::NotRunning);
+    commands.send_event(SceneChange(AppState::GameOver));
+}
+
+/// A hand-authored climb. Positions are the centers of each element in world space.
+#[derive(serde::Deserialize, Asset, TypePath, Debug, Clone)]
+pub struct GameLevel {
+    pub id: String,
+    pub start: Vec2,
+    pub target_height: f32,
+    pub camera_bounds: GameLevelDimensions,
+    #[serde(default)]
+    pub platforms: Vec<LevelPlatform>,
+    #[serde(default)]
+    pub trees: Vec<Vec2>,
+    #[serde(default)]
+    pub apples: Vec<Vec2>,
+    #[serde(default)]
+    pub clocks: Vec<Vec2>,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct LevelPlatform {
+    pub position: Vec2,
+    #[serde(default = "LevelPlatform::default_width")]
+    pub width: f32,
+}
+
+impl LevelPlatform {
+    fn default_width() -> f32 {
+        100.0
+    }
+}
+
+/// Spawns every element of `level`, using the art of the biome each element sits in.
+pub fn spawn_level(
+    commands: &mut Commands,
+    assets: &ImageAssets,
+    biomes: Option<&BiomeSet>,
+    level: &GameLevel,
+) {
+    let art_at = |position: Vec2| {
+        let (_, j) = worldgen::tile_at(position);
+        BiomeArt::at(biomes, j, assets)
+    };
+
+    for platform in &level.platforms {
+        let aabb = Aabb2d::new(platform.position, Vec2::new(platform.width / 2.0, 10.0));
+        commands.spawn((
+            StateScoped(AppState::Game),
+            Platform,
+            Sprite {
+                custom_size: Some(Vec2::new(platform.width, 20.)),
+                ..art_at(platform.position).platform
+            },
+            Transform::from_xyz(platform.position.x, platform.position.y, -1.),
+            Obstacle { aabb },
+        ));
+    }
+
+    for tree in &level.trees {
+        commands.spawn((
+            StateScoped(AppState::Game),
+            Obstacle {
+                aabb: worldgen::tree_aabb(*tree),
+            },
+            Sprite {
+                custom_size: Some(Vec2::new(50., 380.)),
+                ..art_at(*tree).tree
+            },
+            Transform::from_xyz(tree.x, tree.y, -5.),
+        ));
+    }
+
+    for apple in &level.apples {
+        commands.spawn((
+            StateScoped(AppState::Game),
+            Apple {
+                aabb: Aabb2d::new(*apple, Vec2::new(8.0, 8.0)),
+            },
+            Sprite {
+                image: assets.apple.clone(),
+                custom_size: Some(Vec2::new(30., 30.)),
+                ..default()
+            },
+            Transform::from_xyz(apple.x, apple.y, -4.),
+        ));
+    }
+
+    for clock in &level.clocks {
+        commands.spawn((
+            StateScoped(AppState::Game),
+            TimeExtender {
+                aabb: Aabb2d::new(*clock, Vec2::new(8.0, 8.0)),
+            },
+            Sprite {
+                image: assets.clock.clone(),
+                ..default()
+            },
+            Transform::from_xyz(clock.x, clock.y, -4.),
+        ));
+    }
+}
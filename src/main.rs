@@ -1,11 +1,24 @@
+mod animation;
 mod app;
 mod assets;
+mod biome;
 mod camera;
 #[cfg(feature = "dev")]
 mod dev_tools;
+mod difficulty;
+mod feedback;
 mod game;
+mod input;
+mod level;
 mod menu;
+mod mode;
+mod pause;
+mod players;
+mod ruleset;
+mod spatial;
+mod touch;
 mod util;
+mod worldgen;
 
 fn main() {
     app::start();
@@ -1,8 +1,13 @@
 use super::ChangeMenu;
 use crate::app::AppState;
 use crate::app::DisplayLanguage;
-use crate::game::PostHighScore;
+use crate::game::FetchHighScores;
+use crate::game::RequestedSeed;
 use crate::game::SceneChange;
+use crate::level::SelectedLevel;
+use crate::mode::GameMode;
+use crate::players::Players;
+use crate::worldgen::seed_from_text;
 
 use bevy::prelude::*;
 
@@ -10,6 +15,40 @@ pub fn start_game(mut commands: Commands) {
     commands.send_event(SceneChange(AppState::Game));
 }
 
+pub fn start_seeded_game(
+    commands: Commands,
+    mut requested_seed: ResMut<RequestedSeed>,
+    seed_text: &str,
+) {
+    requested_seed.0 = seed_from_text(seed_text);
+    start_game(commands);
+}
+
+pub fn start_level(commands: Commands, mut selected_level: ResMut<SelectedLevel>, level: &str) {
+    selected_level.0 = Some(level.to_string());
+    start_game(commands);
+}
+
+pub fn start_mode(
+    commands: Commands,
+    mut game_mode: ResMut<GameMode>,
+    mut players: ResMut<Players>,
+    mode: &str,
+) {
+    // Two player runs play the Classic rules
+    let (choice, count) = match Players::from_id(mode) {
+        Some(count) => (Some(GameMode::Classic), count),
+        None => (GameMode::from_id(mode), Players::Single),
+    };
+    let Some(choice) = choice else {
+        warn!(mode, "Unknown game mode");
+        return;
+    };
+    *game_mode = choice;
+    *players = count;
+    start_game(commands);
+}
+
 pub fn language_selection(
     mut display_language: ResMut<DisplayLanguage>,
     language: impl Into<String>,
@@ -22,7 +61,8 @@ pub fn menu_selection(mut change_menu: EventWriter<ChangeMenu>, menu: impl Into<
 }
 
 pub fn show_leaderboard(mut commands: Commands) {
-    commands.send_event(PostHighScore);
+    commands.send_event(FetchHighScores);
+    commands.send_event(SceneChange(AppState::HighScores));
 }
 
 pub fn show_credits(mut commands: Commands) {
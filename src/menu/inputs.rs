@@ -1,12 +1,19 @@
 use super::{
-    ActiveMenu, ChangeMenu, CurrentSelection, GoToMenu, Language, SelectionMarker, actions,
+    ActiveMenu, ChangeMenu, CurrentSelection, GoToMenu, Language, LevelChoice, ModeChoice,
+    SeedInput, SelectionMarker, actions,
 };
 use crate::app::AppState;
 use crate::app::DisplayLanguage;
 use crate::app::InteractionRateLimit;
+use crate::game::RequestedSeed;
+use crate::input::Action;
+use crate::level::SelectedLevel;
+use crate::mode::GameMode;
+use crate::players::Players;
 
 use bevy::input::common_conditions::input_just_pressed;
 use bevy::prelude::*;
+use bevy_simple_text_input::TextInputValue;
 
 pub(super) fn plugin(app: &mut App) {
     app.insert_resource(CurrentSelection::default());
@@ -18,41 +25,25 @@ pub(super) fn plugin(app: &mut App) {
 
     app.add_systems(
         Update,
-        keyboard_selection.run_if(input_just_pressed(KeyCode::Enter).and(in_state(AppState::Menu))),
+        keyboard_selection
+            .run_if(input_just_pressed(Action::Confirm).and(in_state(AppState::Menu))),
+    );
+
+    app.add_systems(
+        Update,
+        back_selection.run_if(input_just_pressed(Action::Back).and(in_state(AppState::Menu))),
     );
 }
 
 fn keyboard_menu_selection_system(
     time: Res<Time>,
     mut interaction_rate_limit: ResMut<InteractionRateLimit>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    gamepads: Query<&Gamepad>,
+    actions: Res<ButtonInput<Action>>,
     dialog_message: ResMut<ActiveMenu>,
     mut current_selection: ResMut<CurrentSelection>,
 ) {
-    let (_right, _left, _, up, down, _pause) = match gamepads.iter().next() {
-        Some(gamepad) => {
-            let left_stick_x = gamepad.get(GamepadAxis::LeftStickX).unwrap();
-            let left_stick_y = gamepad.get(GamepadAxis::LeftStickY).unwrap();
-
-            (
-                left_stick_x > 0.075,  //right
-                left_stick_x < -0.075, //left
-                gamepad.any_just_pressed([
-                    GamepadButton::North,
-                    GamepadButton::South,
-                    GamepadButton::East,
-                    GamepadButton::West,
-                ]),
-                left_stick_y > 0.075, //up
-                left_stick_y < -0.75, //down
-                gamepad.just_pressed(GamepadButton::Start),
-            )
-        }
-        None => (false, false, false, false, false, false),
-    };
-    let up_key_pressed = up || keyboard_input.any_pressed([KeyCode::KeyW, KeyCode::ArrowUp]);
-    let down_key_pressed = down || keyboard_input.any_pressed([KeyCode::KeyS, KeyCode::ArrowDown]);
+    let up_key_pressed = actions.pressed(Action::Up);
+    let down_key_pressed = actions.pressed(Action::Down);
 
     let dialog = match &dialog_message.opt {
         Some(d) => d,
@@ -91,7 +82,7 @@ fn keyboard_menu_selection_system(
         };
 
         let new_index = if up_key_pressed {
-            if index <= 0 { 0 } else { index - 1 }
+            index.saturating_sub(1)
         } else if down_key_pressed {
             if index >= choices.len() - 1 {
                 choices.len() - 1
@@ -115,6 +106,11 @@ fn keyboard_selection(
     current_selection: ResMut<CurrentSelection>,
 
     display_language: ResMut<DisplayLanguage>,
+    requested_seed: ResMut<RequestedSeed>,
+    selected_level: ResMut<SelectedLevel>,
+    game_mode: ResMut<GameMode>,
+    players: ResMut<Players>,
+    seed_input: Query<&TextInputValue, With<SeedInput>>,
     // assets: Res<CustomAssets>,
     commands: Commands,
 ) {
@@ -125,11 +121,21 @@ fn keyboard_selection(
         return;
     };
 
-    match &choice.choice.action {
-        Some(action) => match action.as_str() {
+    if let Some(action) = &choice.choice.action {
+        match action.as_str() {
             "start_game" => {
                 actions::start_game(commands);
             }
+            "start_seeded_game" => {
+                let seed_text = seed_input.single().map(|t| t.0.clone()).unwrap_or_default();
+                actions::start_seeded_game(commands, requested_seed, &seed_text);
+            }
+            "start_level" => {
+                actions::start_level(commands, selected_level, &choice.id);
+            }
+            "start_mode" => {
+                actions::start_mode(commands, game_mode, players, &choice.id);
+            }
             "show_credits" => {
                 actions::show_credits(commands);
             }
@@ -140,15 +146,25 @@ fn keyboard_selection(
                 actions::language_selection(display_language, action);
             }
             _ => {}
-        },
-        None => {}
+        }
     }
 
-    match &choice.choice.next_id {
-        Some(id) => {
-            actions::menu_selection(change_menu, id);
-        }
-        None => {}
+    if let Some(id) = &choice.choice.next_id {
+        actions::menu_selection(change_menu, id);
+    }
+}
+
+/// Follows the menu's "back" choice, if it has one.
+fn back_selection(change_menu: EventWriter<ChangeMenu>, active_menu: Res<ActiveMenu>) {
+    let back = active_menu
+        .opt
+        .as_ref()
+        .and_then(|menu| menu.choices.as_ref())
+        .and_then(|choices| choices.iter().find(|choice| choice.id == "back"))
+        .and_then(|choice| choice.choice.next_id.clone());
+
+    if let Some(id) = back {
+        actions::menu_selection(change_menu, id);
     }
 }
 
@@ -178,6 +194,39 @@ pub fn click_start_game(_: Trigger<Pointer<Click>>, commands: Commands) {
     actions::start_game(commands);
 }
 
+pub fn click_start_seeded_game(
+    _: Trigger<Pointer<Click>>,
+    commands: Commands,
+    requested_seed: ResMut<RequestedSeed>,
+    seed_input: Query<&TextInputValue, With<SeedInput>>,
+) {
+    let seed_text = seed_input.single().map(|t| t.0.clone()).unwrap_or_default();
+    actions::start_seeded_game(commands, requested_seed, &seed_text);
+}
+
+pub fn click_start_level(
+    trigger: Trigger<Pointer<Click>>,
+    commands: Commands,
+    selected_level: ResMut<SelectedLevel>,
+    level_query: Query<&LevelChoice>,
+) {
+    if let Ok(level) = level_query.get(trigger.target) {
+        actions::start_level(commands, selected_level, &level.0);
+    }
+}
+
+pub fn click_start_mode(
+    trigger: Trigger<Pointer<Click>>,
+    commands: Commands,
+    game_mode: ResMut<GameMode>,
+    players: ResMut<Players>,
+    mode_query: Query<&ModeChoice>,
+) {
+    if let Ok(mode) = mode_query.get(trigger.target) {
+        actions::start_mode(commands, game_mode, players, &mode.0);
+    }
+}
+
 pub fn click_show_credits(_: Trigger<Pointer<Click>>, commands: Commands) {
     actions::show_credits(commands);
 }
@@ -1,7 +1,8 @@
-use super::{Choice, SelectionMarker};
+use super::{Choice, SeedInput, SelectionMarker};
 use crate::app::RESOLUTION_HEIGHT;
 use crate::util::handles::BODY_FONT;
 use bevy::{prelude::*, ui::Val::*};
+use bevy_simple_text_input::{TextInput, TextInputTextColor, TextInputTextFont};
 
 #[derive(Component)]
 pub struct MenuOption;
@@ -59,7 +60,7 @@ pub fn button_layout(text: &str, choice: Choice) -> impl Bundle {
                 .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 30.)
                 .with_line_height(bevy::text::LineHeight::RelativeToFont(2.5)),
             Pickable::IGNORE,
-            TextSpan::new(format!("{}", text)),
+            TextSpan::new(text.to_string()),
         )],
     )
 }
@@ -82,7 +83,30 @@ pub fn header_layout(text: &str) -> impl Bundle {
                 .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 30.)
                 .with_line_height(bevy::text::LineHeight::RelativeToFont(2.5)),
             Pickable::IGNORE,
-            TextSpan::new(format!("{}", text)),
+            TextSpan::new(text.to_string()),
         )],
     )
 }
+
+pub fn seed_input_layout() -> impl Bundle {
+    (
+        Name::new("Seed Input"),
+        SeedInput,
+        Node {
+            position_type: PositionType::Relative,
+            width: Val::Px(200.0),
+            left: Val::Px(200.),
+            margin: UiRect::vertical(Px(5.0)),
+            border: UiRect::all(Val::Px(5.0)),
+            padding: UiRect::all(Val::Px(5.0)),
+            ..default()
+        },
+        BorderColor(bevy::color::palettes::css::BLACK.into()),
+        BackgroundColor(bevy::color::palettes::css::WHITE.into()),
+        TextInput,
+        TextInputTextFont(
+            TextFont::from_font(BODY_FONT).with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 30.),
+        ),
+        TextInputTextColor(TextColor(bevy::color::palettes::css::BLACK.into())),
+    )
+}
@@ -43,15 +43,9 @@ impl ActiveMenu {
 #[derive(Component)]
 struct SelectionMarker(Choice);
 
-#[derive(Resource, Debug, Clone, Deref, DerefMut)]
+#[derive(Resource, Debug, Default, Clone, Deref, DerefMut)]
 struct CurrentSelection(Option<Choice>);
 
-impl Default for CurrentSelection {
-    fn default() -> Self {
-        Self(Default::default())
-    }
-}
-
 #[derive(Event)]
 struct ChangeMenu(String);
 
@@ -70,6 +64,27 @@ impl Language {
     }
 }
 
+#[derive(Component)]
+struct SeedInput;
+
+#[derive(Component)]
+struct LevelChoice(String);
+
+impl LevelChoice {
+    fn new(s: impl Into<String>) -> Self {
+        Self(s.into())
+    }
+}
+
+#[derive(Component)]
+struct ModeChoice(String);
+
+impl ModeChoice {
+    fn new(s: impl Into<String>) -> Self {
+        Self(s.into())
+    }
+}
+
 #[derive(Component)]
 struct GoToMenu(String);
 
@@ -90,7 +105,6 @@ fn menu_setup(
     // commands.spawn((StateScoped(AppState::Menu), Camera2d::default()));
 
     commands.send_event(ChangeMenu::new("main menu"));
-    return;
 }
 
 fn change_menu(
@@ -145,57 +159,64 @@ fn change_menu(
                 layouts::menu_layout(RESOLUTION_WIDTH - 2. * padding_x),
             ))
             .with_children(|p| {
-                let text = dialog.lex.from_language(&display_language.0);
+                let text = dialog.lex.in_language(&display_language.0);
 
                 p.spawn(layouts::header_layout(&text));
 
-                match &dialog.choices {
-                    Some(choices) => {
-                        for (_index, choice) in choices.iter().enumerate() {
-                            let text = choice.choice.lex.from_language(&display_language.0);
-
-                            let mut button =
-                                p.spawn((layouts::button_layout(&text, choice.clone()),));
-                            button.observe(inputs::mouse_move);
-                            button.observe(inputs::mouse_over);
-
-                            match &choice.choice.action {
-                                Some(action) => match action.as_str() {
-                                    "start_game" => {
-                                        button.observe(inputs::click_start_game);
-                                    }
-                                    "show_credits" => {
-                                        button.observe(inputs::click_show_credits);
-                                    }
-                                    "show_leaderboard" => {
-                                        button.observe(inputs::click_show_leaderboard);
-                                    }
-                                    "english" | "spanish" => {
-                                        button
-                                            .insert(Language::new(action))
-                                            .observe(inputs::click_language_selection);
-                                    }
-                                    _ => {}
-                                },
-                                None => {}
-                            }
+                if dialog.input.as_deref() == Some("seed") {
+                    p.spawn(layouts::seed_input_layout());
+                }
+
+                if let Some(choices) = &dialog.choices {
+                    for choice in choices.iter() {
+                        let text = choice.choice.lex.in_language(&display_language.0);
 
-                            match &choice.choice.next_id {
-                                Some(id) => {
+                        let mut button = p.spawn((layouts::button_layout(&text, choice.clone()),));
+                        button.observe(inputs::mouse_move);
+                        button.observe(inputs::mouse_over);
+
+                        if let Some(action) = &choice.choice.action {
+                            match action.as_str() {
+                                "start_game" => {
+                                    button.observe(inputs::click_start_game);
+                                }
+                                "start_seeded_game" => {
+                                    button.observe(inputs::click_start_seeded_game);
+                                }
+                                "start_level" => {
                                     button
-                                        .insert(GoToMenu::new(id))
-                                        .observe(inputs::click_menu_selection);
+                                        .insert(LevelChoice::new(&choice.id))
+                                        .observe(inputs::click_start_level);
                                 }
-                                None => {}
+                                "start_mode" => {
+                                    button
+                                        .insert(ModeChoice::new(&choice.id))
+                                        .observe(inputs::click_start_mode);
+                                }
+                                "show_credits" => {
+                                    button.observe(inputs::click_show_credits);
+                                }
+                                "show_leaderboard" => {
+                                    button.observe(inputs::click_show_leaderboard);
+                                }
+                                "english" | "spanish" => {
+                                    button
+                                        .insert(Language::new(action))
+                                        .observe(inputs::click_language_selection);
+                                }
+                                _ => {}
                             }
                         }
+
+                        if let Some(id) = &choice.choice.next_id {
+                            button
+                                .insert(GoToMenu::new(id))
+                                .observe(inputs::click_menu_selection);
+                        }
                     }
-                    None => {}
                 }
             });
     });
-
-    return;
 }
 
 fn move_choice_marker(
@@ -227,7 +248,7 @@ fn move_choice_marker(
     };
 
     for (idx, choice) in choices.iter().enumerate() {
-        let text = choice.choice.lex.from_language(&display_language.0);
+        let text = choice.choice.lex.in_language(&display_language.0);
 
         if current_choice.id == choice.id.clone() {
             for (text_idx, (mut text_span, parent)) in selections.iter_mut().enumerate() {
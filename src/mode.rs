@@ -0,0 +1,254 @@
+use crate::app::AppState;
+use crate::players::Players;
+use bevy::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(GameMode::default())
+        .insert_resource(MaxAltitude::default())
+        .add_systems(OnEnter(AppState::Menu), reset_game_mode)
+        .add_systems(OnEnter(AppState::Game), reset_max_altitude);
+}
+
+/// Rules of the run, picked from the main menu. Seeded runs and levels play Classic.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameMode {
+    /// Reach the target height before the timer or health runs out
+    #[default]
+    Classic,
+    /// No target height, the run lasts as long as the timer and health do
+    Endless,
+    /// Reach the target height as fast as possible
+    TimeAttack,
+    /// No timer and no fall damage
+    Zen,
+}
+
+impl GameMode {
+    /// Every mode with a leaderboard, in the order the high score screen lists them
+    pub const ALL: [Self; 4] = [Self::Classic, Self::Endless, Self::TimeAttack, Self::Zen];
+
+    /// Matches the choice ids in `lexi/menu/modes.json`
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "classic" => Some(Self::Classic),
+            "endless" => Some(Self::Endless),
+            "time_attack" => Some(Self::TimeAttack),
+            "zen" => Some(Self::Zen),
+            _ => None,
+        }
+    }
+
+    /// Leaderboard the run's score is posted to
+    pub fn category(&self) -> &'static str {
+        match self {
+            Self::Classic => "classic",
+            Self::Endless => "endless",
+            Self::TimeAttack => "time_attack",
+            Self::Zen => "zen",
+        }
+    }
+
+    /// Title of the mode's leaderboard
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Classic => "Classic",
+            Self::Endless => "Endless",
+            Self::TimeAttack => "Time Attack",
+            Self::Zen => "Zen",
+        }
+    }
+
+    pub fn has_target(&self) -> bool {
+        *self != Self::Endless
+    }
+
+    /// Whether running out of time ends the run
+    pub fn has_time_limit(&self) -> bool {
+        matches!(self, Self::Classic | Self::Endless)
+    }
+
+    pub fn has_fall_damage(&self) -> bool {
+        *self != Self::Zen
+    }
+
+    /// Time Attack scores are times, so the leaderboard is sorted the other way around
+    pub fn lower_score_wins(&self) -> bool {
+        *self == Self::TimeAttack
+    }
+
+    /// Breakdown and total for the game over screen, `None` if the run doesn't score.
+    pub fn score(&self, run: &RunSummary) -> Option<Score> {
+        let apple_total = run.apples * 12;
+        let cider_total = (run.apples / 10) * 500;
+        let apple_line = format!("Total Apples: {} x 12 = {}", run.apples, apple_total);
+        let cider_line = format!("Total Cider: {} x 500 = {}", run.apples / 10, cider_total);
+
+        // Math
+        // Apples = x12
+        // Time = x2
+        // Every 10th apple = Cider
+        // Cider = 500 pts
+        match self {
+            Self::Classic if run.won => {
+                let time_total = run.time_left as u32 * 2;
+                let total = apple_total + time_total + cider_total;
+                Some(Score {
+                    lines: vec![
+                        apple_line,
+                        cider_line,
+                        format!("Time Remaining: {} x 2 = {}", run.time_left, time_total),
+                    ],
+                    total,
+                    display: total.to_string(),
+                })
+            }
+            Self::Endless => {
+                let altitude = run.max_altitude.max(0.0).ceil() as u32;
+                Some(Score {
+                    lines: vec![format!("Max Altitude: {}", altitude)],
+                    total: altitude,
+                    display: altitude.to_string(),
+                })
+            }
+            Self::TimeAttack if run.won => {
+                // Hundredths of a second so the leaderboard can keep whole numbers
+                let total = (run.elapsed_secs * 100.0).round() as u32;
+                let display = format_time(total);
+                Some(Score {
+                    lines: vec![format!("Time: {}", display)],
+                    total,
+                    display,
+                })
+            }
+            Self::Zen if run.won => {
+                let total = apple_total + cider_total;
+                Some(Score {
+                    lines: vec![apple_line, cider_line],
+                    total,
+                    display: total.to_string(),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// What the run posts to the leaderboard, `None` keeps it off the leaderboards.
+    pub fn leaderboard_score(&self, run: &RunSummary, origin: &RunOrigin) -> Option<Score> {
+        origin.ranked().then(|| self.score(run)).flatten()
+    }
+
+    /// How a leaderboard score of this mode is shown
+    pub fn format_score(&self, score: u32) -> String {
+        match self {
+            Self::TimeAttack => format_time(score),
+            _ => score.to_string(),
+        }
+    }
+}
+
+fn format_time(hundredths: u32) -> String {
+    format!("{}.{:02}s", hundredths / 100, hundredths % 100)
+}
+
+/// What happened during the run, as far as scoring is concerned.
+pub struct RunSummary {
+    pub won: bool,
+    pub apples: u32,
+    pub time_left: f32,
+    pub elapsed_secs: f32,
+    pub max_altitude: f32,
+}
+
+/// How the run was set up, as far as the leaderboards are concerned.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RunOrigin {
+    pub players: Players,
+    /// Hand-made levels are short and known in advance, so they don't compare to the climb
+    pub level: bool,
+    /// A seed that was typed in or replayed could be farmed until it gives a high score
+    pub seeded: bool,
+}
+
+impl RunOrigin {
+    pub fn ranked(&self) -> bool {
+        self.players == Players::Single && !self.level && !self.seeded
+    }
+}
+
+pub struct Score {
+    pub lines: Vec<String>,
+    pub total: u32,
+    pub display: String,
+}
+
+/// Highest the dino got above the starting platform during the run.
+#[derive(Resource, Default)]
+pub struct MaxAltitude(pub f32);
+
+fn reset_game_mode(mut game_mode: ResMut<GameMode>) {
+    *game_mode = GameMode::Classic;
+}
+
+fn reset_max_altitude(mut max_altitude: ResMut<MaxAltitude>) {
+    max_altitude.0 = 0.0;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn won_run() -> RunSummary {
+        RunSummary {
+            won: true,
+            apples: 20,
+            time_left: 200.0,
+            elapsed_secs: 20.0,
+            max_altitude: 1280.0,
+        }
+    }
+
+    #[test]
+    fn solo_climbs_reach_the_leaderboard() {
+        let origin = RunOrigin::default();
+        for mode in GameMode::ALL {
+            assert!(mode.leaderboard_score(&won_run(), &origin).is_some());
+        }
+    }
+
+    #[test]
+    fn level_wins_never_reach_the_leaderboard() {
+        let origin = RunOrigin {
+            level: true,
+            ..default()
+        };
+        for mode in GameMode::ALL {
+            assert!(mode.leaderboard_score(&won_run(), &origin).is_none());
+        }
+    }
+
+    #[test]
+    fn chosen_seeds_never_reach_the_leaderboard() {
+        let origin = RunOrigin {
+            seeded: true,
+            ..default()
+        };
+        for mode in GameMode::ALL {
+            assert!(mode.leaderboard_score(&won_run(), &origin).is_none());
+        }
+    }
+
+    #[test]
+    fn two_player_runs_never_reach_the_leaderboard() {
+        for players in [Players::Coop, Players::Versus] {
+            let origin = RunOrigin {
+                players,
+                ..default()
+            };
+            assert!(
+                GameMode::Classic
+                    .leaderboard_score(&won_run(), &origin)
+                    .is_none()
+            );
+        }
+    }
+}
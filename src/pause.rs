@@ -0,0 +1,555 @@
+use crate::app::{AppState, DisplayLanguage, RESOLUTION_HEIGHT};
+use crate::assets::lexi::pause::PauseLex;
+use crate::feedback::ReducedMotion;
+use crate::game::{
+    self, GameMusic, GameState, RequestedSeed, RunSeed, SceneChange, SfxMusicVolume,
+    VolumeToggleMusicMarker, VolumeToggleSfxMarker,
+};
+use crate::input::{self, Action, Binding, InputBindings, Rebinding};
+use crate::util::handles::BODY_FONT;
+use bevy::audio::AudioSinkPlayback;
+use bevy::input::common_conditions::input_just_pressed;
+use bevy::prelude::*;
+use bevy::window::WindowFocused;
+use bevy_aspect_ratio_mask::Hud;
+use std::time::Duration;
+
+/// Longest frame the game simulates in one go. Anything longer, like a frame that spans a
+/// hidden browser tab, is slowed down instead of skipping the dino through platforms.
+const MAX_FRAME_DELTA: Duration = Duration::from_millis(100);
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_event::<PauseAction>()
+        .insert_resource(PausePage::default())
+        .insert_resource(PauseSelection::default())
+        .add_systems(Startup, clamp_frame_delta)
+        .add_systems(
+            Update,
+            (
+                toggle_pause.run_if(input_just_pressed(Action::Pause).and(input::not_rebinding)),
+                auto_pause.run_if(on_event::<WindowFocused>),
+                continue_after_focus_loss
+                    .run_if(in_state(GameState::Paused).and(resource_equals(PausePage::FocusLost))),
+            )
+                .run_if(in_state(AppState::Game)),
+        )
+        // The overlay is built on entering the pause rather than from the `PausePage` change,
+        // which is made while the game is still running and the menu systems are skipped
+        .add_systems(
+            OnEnter(GameState::Paused),
+            (freeze_time, pause_music, spawn_pause_menu),
+        )
+        .add_systems(OnExit(GameState::Paused), (unfreeze_time, resume_music))
+        .add_systems(
+            Update,
+            (
+                spawn_pause_menu.run_if(
+                    resource_changed::<PausePage>
+                        .or(resource_changed::<SfxMusicVolume>)
+                        .or(resource_changed::<ReducedMotion>)
+                        .or(resource_changed::<InputBindings>)
+                        .or(resource_changed::<Rebinding>),
+                ),
+                pause_menu_navigation.run_if(input::not_rebinding),
+                handle_pause_action.run_if(on_event::<PauseAction>),
+                highlight_pause_selection,
+            )
+                .chain()
+                .run_if(in_state(AppState::Game).and(in_state(GameState::Paused))),
+        );
+}
+
+/// Pausing only makes sense mid-run, before the first jump there is nothing to freeze.
+fn toggle_pause(
+    game_state: Res<State<GameState>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut page: ResMut<PausePage>,
+    mut selection: ResMut<PauseSelection>,
+) {
+    match game_state.get() {
+        GameState::Running => {
+            next_game_state.set(GameState::Paused);
+            *page = PausePage::Main;
+            selection.0 = 0;
+        }
+        GameState::Paused => next_game_state.set(GameState::Running),
+        GameState::NotRunning => {}
+    }
+}
+
+/// Pauses when the window or browser tab loses focus, nothing keeps running in the background.
+fn auto_pause(
+    mut reader: EventReader<WindowFocused>,
+    game_state: Res<State<GameState>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut page: ResMut<PausePage>,
+) {
+    let lost_focus = reader.read().any(|event| !event.focused);
+    if lost_focus && *game_state.get() == GameState::Running {
+        info!("Window lost focus, pausing");
+        next_game_state.set(GameState::Paused);
+        *page = PausePage::FocusLost;
+    }
+}
+
+/// Any key, button, click or touch picks the run back up after an automatic pause.
+fn continue_after_focus_loss(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    gamepads: Query<&Gamepad>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    let pressed = keyboard_input.get_just_pressed().next().is_some()
+        || mouse_input.get_just_pressed().next().is_some()
+        || touches.any_just_pressed()
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.get_just_pressed().next().is_some());
+
+    if pressed {
+        next_game_state.set(GameState::Running);
+    }
+}
+
+fn clamp_frame_delta(mut time: ResMut<Time<Virtual>>) {
+    time.set_max_delta(MAX_FRAME_DELTA);
+}
+
+/// Stops game time altogether, so the frame that ends the pause starts from a normal delta
+/// no matter how long the game was left alone.
+fn freeze_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn unfreeze_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PauseAction(pub PauseChoice);
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PauseChoice {
+    Resume,
+    Restart,
+    Settings,
+    Quit,
+    ToggleMusic,
+    ToggleSfx,
+    ToggleReducedMotion,
+    Controls,
+    /// Waits for the next key or button and binds it to the action
+    Rebind(Action),
+    ResetControls,
+    Back,
+}
+
+impl PauseChoice {
+    /// Matches the ids in `lexi/pause`
+    fn lex_id(&self) -> &'static str {
+        match self {
+            Self::Resume => "resume",
+            Self::Restart => "restart",
+            Self::Settings => "settings",
+            Self::Quit => "quit",
+            Self::ToggleMusic => "music",
+            Self::ToggleSfx => "sound",
+            Self::ToggleReducedMotion => "reduce_motion",
+            Self::Controls => "controls",
+            Self::Rebind(Action::Jump) => "action_jump",
+            Self::Rebind(Action::Left) => "action_left",
+            Self::Rebind(Action::Right) => "action_right",
+            Self::Rebind(Action::Attack) => "action_attack",
+            Self::Rebind(Action::Pause) => "action_pause",
+            Self::Rebind(_) => "",
+            Self::ResetControls => "reset_controls",
+            Self::Back => "back",
+        }
+    }
+}
+
+/// Which list of choices the pause overlay is showing.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PausePage {
+    #[default]
+    Main,
+    Settings,
+    Controls,
+    /// Paused automatically, only asks to press something to continue
+    FocusLost,
+}
+
+impl PausePage {
+    fn choices(&self) -> &'static [PauseChoice] {
+        match self {
+            Self::Main => &[
+                PauseChoice::Resume,
+                PauseChoice::Restart,
+                PauseChoice::Settings,
+                PauseChoice::Quit,
+            ],
+            Self::Settings => &[
+                PauseChoice::ToggleMusic,
+                PauseChoice::ToggleSfx,
+                PauseChoice::ToggleReducedMotion,
+                PauseChoice::Controls,
+                PauseChoice::Back,
+            ],
+            Self::Controls => &[
+                PauseChoice::Rebind(Action::REBINDABLE[0]),
+                PauseChoice::Rebind(Action::REBINDABLE[1]),
+                PauseChoice::Rebind(Action::REBINDABLE[2]),
+                PauseChoice::Rebind(Action::REBINDABLE[3]),
+                PauseChoice::Rebind(Action::REBINDABLE[4]),
+                PauseChoice::ResetControls,
+                PauseChoice::Back,
+            ],
+            Self::FocusLost => &[],
+        }
+    }
+}
+
+/// Index of the highlighted choice on the current page.
+#[derive(Resource, Default)]
+pub struct PauseSelection(pub usize);
+
+#[derive(Component)]
+pub struct PauseMenu;
+
+fn pause_music(music: Query<&AudioSink, With<GameMusic>>) {
+    for sink in music.iter() {
+        sink.pause();
+    }
+}
+
+fn resume_music(music: Query<&AudioSink, With<GameMusic>>) {
+    for sink in music.iter() {
+        sink.play();
+    }
+}
+
+/// Builds the overlay for the current page, replacing the previous one.
+fn spawn_pause_menu(
+    mut commands: Commands,
+    hud: Res<Hud>,
+    page: Res<PausePage>,
+    language: Res<DisplayLanguage>,
+    lexes: Res<Assets<PauseLex>>,
+    sfx_music_volume: Res<SfxMusicVolume>,
+    reduced_motion: Res<ReducedMotion>,
+    bindings: Res<InputBindings>,
+    rebinding: Res<Rebinding>,
+    menus: Query<Entity, With<PauseMenu>>,
+) {
+    for entity in menus.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    let lex = |id: &str| {
+        lexes
+            .iter()
+            .find(|(_, data)| data.id == id)
+            .map(|(_, data)| data.lex.in_language(&language.0))
+            .unwrap_or_default()
+    };
+    let on_off = |on: bool| lex(if on { "on" } else { "off" });
+
+    commands.entity(hud.0).with_children(|parent| {
+        parent
+            .spawn((
+                StateScoped(GameState::Paused),
+                PauseMenu,
+                Node {
+                    position_type: PositionType::Absolute,
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Column,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    padding: UiRect::vertical(Val::Px(55.0)),
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+                ZIndex(50),
+            ))
+            .with_children(|p| {
+                p.spawn((
+                    TextFont::from_font(BODY_FONT)
+                        .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 15.),
+                    Text(lex("paused")),
+                ));
+
+                if *page == PausePage::FocusLost {
+                    p.spawn((
+                        TextFont::from_font(BODY_FONT)
+                            .with_font_size(RESOLUTION_HEIGHT * 6. / 8. / 25.),
+                        Text(lex("continue")),
+                    ));
+                }
+
+                for (index, choice) in page.choices().iter().enumerate() {
+                    let text = match choice {
+                        PauseChoice::ToggleMusic => {
+                            format!("{}: {}", lex("music"), on_off(sfx_music_volume.music))
+                        }
+                        PauseChoice::ToggleSfx => {
+                            format!("{}: {}", lex("sound"), on_off(sfx_music_volume.sfx))
+                        }
+                        PauseChoice::ToggleReducedMotion => {
+                            format!("{}: {}", lex("reduce_motion"), on_off(reduced_motion.0))
+                        }
+                        PauseChoice::Rebind(action) if rebinding.0 == Some(*action) => {
+                            format!("{}: {}", lex(choice.lex_id()), lex("waiting"))
+                        }
+                        PauseChoice::Rebind(action) => {
+                            let keys = bindings.get(*action);
+                            let key = keys.iter().find(|b| matches!(b, Binding::Key(_)));
+                            let button = keys.iter().find(|b| matches!(b, Binding::Button(_)));
+                            let labels = key.into_iter().chain(button).map(Binding::label);
+                            format!(
+                                "{}: {}",
+                                lex(choice.lex_id()),
+                                labels.collect::<Vec<_>>().join(" / ")
+                            )
+                        }
+                        _ => lex(choice.lex_id()),
+                    };
+
+                    let button = if *page == PausePage::Controls {
+                        game::button_sized(text, 280.0, 40.0)
+                    } else {
+                        game::button_sized(text, 150.0, 65.0)
+                    };
+                    p.spawn((button, *choice))
+                        .observe(
+                            move |_: Trigger<Pointer<Over>>,
+                                  mut selection: ResMut<PauseSelection>| {
+                                selection.0 = index;
+                            },
+                        )
+                        .observe(
+                            |trigger: Trigger<Pointer<Click>>,
+                             choices: Query<&PauseChoice>,
+                             mut actions: EventWriter<PauseAction>| {
+                                if let Ok(choice) = choices.get(trigger.target()) {
+                                    actions.write(PauseAction(*choice));
+                                }
+                            },
+                        );
+                }
+            });
+    });
+}
+
+fn pause_menu_navigation(
+    input: Res<ButtonInput<Action>>,
+    page: Res<PausePage>,
+    mut selection: ResMut<PauseSelection>,
+    mut actions: EventWriter<PauseAction>,
+) {
+    let up = input.just_pressed(Action::Up);
+    let down = input.just_pressed(Action::Down);
+    let confirm = input.just_pressed(Action::Confirm);
+    let back = input.just_pressed(Action::Back);
+
+    let choices = page.choices();
+    if choices.is_empty() {
+        return;
+    }
+
+    if up {
+        selection.0 = selection.0.saturating_sub(1);
+    } else if down {
+        selection.0 = (selection.0 + 1).min(choices.len() - 1);
+    }
+
+    if confirm {
+        actions.write(PauseAction(choices[selection.0.min(choices.len() - 1)]));
+    } else if back {
+        actions.write(PauseAction(match *page {
+            PausePage::Main | PausePage::FocusLost => PauseChoice::Resume,
+            PausePage::Settings | PausePage::Controls => PauseChoice::Back,
+        }));
+    }
+}
+
+fn handle_pause_action(
+    mut commands: Commands,
+    mut reader: EventReader<PauseAction>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut page: ResMut<PausePage>,
+    mut selection: ResMut<PauseSelection>,
+    mut sfx_music_volume: ResMut<SfxMusicVolume>,
+    mut reduced_motion: ResMut<ReducedMotion>,
+    mut bindings: ResMut<InputBindings>,
+    mut rebinding: ResMut<Rebinding>,
+    run_seed: Res<RunSeed>,
+    mut requested_seed: ResMut<RequestedSeed>,
+    mut music_icon: Query<&mut ImageNode, With<VolumeToggleMusicMarker>>,
+    mut sfx_icon: Query<
+        &mut ImageNode,
+        (
+            With<VolumeToggleSfxMarker>,
+            Without<VolumeToggleMusicMarker>,
+        ),
+    >,
+) {
+    // Only the first action counts, the rest were aimed at a page that is going away
+    let Some(PauseAction(choice)) = reader.read().next().copied() else {
+        return;
+    };
+    reader.clear();
+
+    match choice {
+        PauseChoice::Resume => next_game_state.set(GameState::Running),
+        PauseChoice::Restart => {
+            // Same seed, mode and level, just from the start
+            requested_seed.0 = Some(run_seed.0);
+            next_game_state.set(GameState::NotRunning);
+            commands.send_event(SceneChange(AppState::Restarting));
+        }
+        PauseChoice::Settings => {
+            *page = PausePage::Settings;
+            selection.0 = 0;
+        }
+        PauseChoice::Quit => {
+            next_game_state.set(GameState::NotRunning);
+            commands.send_event(SceneChange(AppState::Menu));
+        }
+        PauseChoice::ToggleMusic => {
+            sfx_music_volume.music = !sfx_music_volume.music;
+            set_volume_icon(music_icon.single_mut().ok(), sfx_music_volume.music);
+        }
+        PauseChoice::ToggleSfx => {
+            sfx_music_volume.sfx = !sfx_music_volume.sfx;
+            set_volume_icon(sfx_icon.single_mut().ok(), sfx_music_volume.sfx);
+        }
+        PauseChoice::ToggleReducedMotion => reduced_motion.0 = !reduced_motion.0,
+        PauseChoice::Controls => {
+            *page = PausePage::Controls;
+            selection.0 = 0;
+        }
+        PauseChoice::Rebind(action) => rebinding.0 = Some(action),
+        PauseChoice::ResetControls => *bindings = InputBindings::default(),
+        PauseChoice::Back => {
+            *page = match *page {
+                PausePage::Controls => PausePage::Settings,
+                _ => PausePage::Main,
+            };
+            selection.0 = 0;
+        }
+    }
+}
+
+/// Keeps the volume toggles on the HUD in sync with the settings page.
+fn set_volume_icon(icon: Option<Mut<ImageNode>>, on: bool) {
+    if let Some(atlas) = icon.and_then(|icon| icon.into_inner().texture_atlas.as_mut()) {
+        atlas.index = if on { 0 } else { 1 };
+    }
+}
+
+/// Outlines the selected button so the overlay can be driven without a pointer.
+fn highlight_pause_selection(
+    selection: Res<PauseSelection>,
+    page: Res<PausePage>,
+    choices: Query<(&PauseChoice, &Children)>,
+    mut borders: Query<&mut BorderColor, With<Button>>,
+) {
+    let selected = page.choices().get(selection.0);
+    for (choice, children) in choices.iter() {
+        for child in children.iter() {
+            if let Ok(mut border) = borders.get_mut(child) {
+                border.0 = if Some(choice) == selected {
+                    Color::WHITE
+                } else {
+                    Color::BLACK
+                };
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::input::InputPlugin;
+    use bevy::state::app::StatesPlugin;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin, InputPlugin))
+            .init_state::<AppState>()
+            .init_state::<GameState>()
+            .add_event::<WindowFocused>()
+            .init_resource::<ButtonInput<Action>>()
+            .init_resource::<Assets<PauseLex>>()
+            .insert_resource(DisplayLanguage("english".into()))
+            .insert_resource(SfxMusicVolume::default())
+            .insert_resource(ReducedMotion::default())
+            .insert_resource(InputBindings::default())
+            .insert_resource(Rebinding::default())
+            .add_plugins(plugin);
+        let hud = app.world_mut().spawn(Node::default()).id();
+        app.insert_resource(Hud(hud));
+
+        app.world_mut()
+            .resource_mut::<NextState<AppState>>()
+            .set(AppState::Game);
+        app.world_mut()
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::Running);
+        app.update();
+        app
+    }
+
+    fn pause_menus(app: &mut App) -> usize {
+        app.world_mut()
+            .query_filtered::<(), With<PauseMenu>>()
+            .iter(app.world())
+            .count()
+    }
+
+    #[test]
+    fn pausing_always_spawns_the_overlay() {
+        let mut app = app();
+        for _ in 0..3 {
+            app.world_mut()
+                .resource_mut::<ButtonInput<Action>>()
+                .press(Action::Pause);
+            app.update();
+            app.world_mut()
+                .resource_mut::<ButtonInput<Action>>()
+                .reset_all();
+            app.update();
+            assert_eq!(
+                *app.world().resource::<State<GameState>>(),
+                GameState::Paused
+            );
+            assert_eq!(pause_menus(&mut app), 1);
+
+            app.world_mut()
+                .resource_mut::<NextState<GameState>>()
+                .set(GameState::Running);
+            app.update();
+            assert_eq!(pause_menus(&mut app), 0);
+        }
+    }
+
+    #[test]
+    fn losing_focus_spawns_the_overlay() {
+        let mut app = app();
+        let window = app.world_mut().spawn_empty().id();
+        app.world_mut().send_event(WindowFocused {
+            window,
+            focused: false,
+        });
+        app.update();
+        app.update();
+        assert_eq!(
+            *app.world().resource::<State<GameState>>(),
+            GameState::Paused
+        );
+        assert_eq!(pause_menus(&mut app), 1);
+    }
+}
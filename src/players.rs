@@ -0,0 +1,117 @@
+use crate::app::{AppState, RESOLUTION_HEIGHT};
+use crate::game::{Dino, GameState, GameStatus, Player, SceneChange};
+use bevy::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(Players::default())
+        .insert_resource(Winner::default())
+        .add_systems(OnEnter(AppState::Menu), reset_players)
+        .add_systems(OnEnter(AppState::Game), reset_winner)
+        .add_systems(
+            Update,
+            keep_players_together.run_if(
+                in_state(AppState::Game)
+                    .and(in_state(GameState::Running))
+                    .and(multiplayer),
+            ),
+        );
+}
+
+/// How many dinos climb and whether they climb together, picked from the modes menu.
+/// Two player runs play by the Classic rules.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Players {
+    #[default]
+    Single,
+    /// Both dinos share the clock and the camera, and win or lose together
+    Coop,
+    /// The first dino to reach the target height wins
+    Versus,
+}
+
+impl Players {
+    /// Matches the choice ids in `lexi/menu/modes.json`
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "coop" => Some(Self::Coop),
+            "versus" => Some(Self::Versus),
+            _ => None,
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        match self {
+            Self::Single => 1,
+            Self::Coop | Self::Versus => 2,
+        }
+    }
+
+    /// Where a player starts relative to the level's start, side by side so they don't overlap
+    pub fn start_offset(&self, player: usize) -> Vec2 {
+        match self {
+            Self::Single => Vec2::ZERO,
+            Self::Coop | Self::Versus if player == 0 => Vec2::new(-40.0, 0.0),
+            Self::Coop | Self::Versus => Vec2::new(40.0, 0.0),
+        }
+    }
+}
+
+/// Run condition for anything only two player runs need
+pub fn multiplayer(players: Res<Players>) -> bool {
+    *players != Players::Single
+}
+
+/// Tells the dinos and their HUD apart, the first player keeps the original colors.
+pub fn tint(player: usize) -> Color {
+    if player == 0 {
+        Color::WHITE
+    } else {
+        Color::srgb(0.6, 0.8, 1.0)
+    }
+}
+
+/// Index of the player who won a versus run
+#[derive(Resource, Default, Debug)]
+pub struct Winner(pub Option<usize>);
+
+fn reset_players(mut players: ResMut<Players>) {
+    *players = Players::Single;
+}
+
+fn reset_winner(mut winner: ResMut<Winner>) {
+    winner.0 = None;
+}
+
+/// The camera can only show both dinos while they are less than a screen apart. A co-op run
+/// is lost once they drift further, in versus the dino left behind loses.
+fn keep_players_together(
+    mut commands: Commands,
+    dinos: Query<(&Player, &Transform), With<Dino>>,
+    players: Res<Players>,
+    mut winner: ResMut<Winner>,
+    mut game_status: ResMut<GameStatus>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    let by_height = |a: &(&Player, &Transform), b: &(&Player, &Transform)| {
+        a.1.translation.y.total_cmp(&b.1.translation.y)
+    };
+    let (Some(lowest), Some(highest)) = (
+        dinos.iter().min_by(by_height),
+        dinos.iter().max_by(by_height),
+    ) else {
+        return;
+    };
+
+    if highest.1.translation.y - lowest.1.translation.y <= RESOLUTION_HEIGHT {
+        return;
+    }
+
+    if *players == Players::Versus {
+        winner.0 = Some(highest.0.0);
+        *game_status = GameStatus::Win;
+    } else {
+        *game_status = GameStatus::Lose;
+    }
+    game_state.set(GameState::NotRunning);
+    commands.send_event(SceneChange(AppState::GameOver));
+}
@@ -0,0 +1,188 @@
+use crate::assets::custom::DataAssets;
+use bevy::prelude::*;
+use bevy_common_assets::json::JsonAssetPlugin;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins(JsonAssetPlugin::<Ruleset>::new(&["ruleset.json"]))
+        .insert_resource(Ruleset::default())
+        .add_systems(
+            Update,
+            sync_ruleset.run_if(
+                resource_exists::<DataAssets>
+                    .and(resource_added::<DataAssets>.or(on_event::<AssetEvent<Ruleset>>)),
+            ),
+        );
+}
+
+/// Everything designers tune about how the game plays, loaded from `data/game.ruleset.json`.
+/// The resource is a copy of the asset, so with `dev_native` builds edits to the file apply
+/// while the game is running. Timer and target changes take effect on the next run.
+#[derive(serde::Deserialize, Asset, TypePath, Resource, Debug, Clone)]
+pub struct Ruleset {
+    pub physics: Physics,
+    /// Seconds on the clock at the start of a run
+    pub game_time: f32,
+    /// Seconds added by collecting a clock
+    pub clock_bonus: f32,
+    pub fall_damage: FallDamage,
+    /// Height the dino has to reach in the endless climb
+    pub target_height: f32,
+}
+
+impl Default for Ruleset {
+    fn default() -> Self {
+        Self {
+            physics: Physics::default(),
+            game_time: 200.0,
+            clock_bonus: 60.0,
+            fall_damage: FallDamage::default(),
+            target_height: 9822.0, // Makes 10k when the dino lands on the starting platform
+        }
+    }
+}
+
+#[derive(serde::Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Physics {
+    pub gravity: f32,
+    pub jump_height: f32,
+    /// Seconds the jump keeps accelerating while held
+    pub max_jump_time: f32,
+    pub running_speed: f32,
+    /// How much horizontal velocity carries over each step (0.0 = instant, 1.0 = no change)
+    pub dampening: f32,
+}
+
+impl Physics {
+    /// Keeps hand-edited values physical, e.g. gravity always pulls down and a jump always
+    /// leaves the ground.
+    pub fn sanitized(self) -> Self {
+        Self {
+            gravity: -self.gravity.abs().max(100.0),
+            jump_height: self.jump_height.max(100.0),
+            max_jump_time: within(self.max_jump_time, 0.05, 1.0),
+            running_speed: self.running_speed.max(10.0),
+            dampening: within(self.dampening, 0.0, 1.0),
+        }
+    }
+}
+
+/// Like `clamp`, but a NaN from a bad edit ends up at `min` instead of staying NaN
+fn within(value: f32, min: f32, max: f32) -> f32 {
+    if value.is_nan() {
+        min
+    } else {
+        value.clamp(min, max)
+    }
+}
+
+impl Default for Physics {
+    fn default() -> Self {
+        Self {
+            gravity: -1200.0,
+            jump_height: 1500.0,
+            max_jump_time: 0.22,
+            running_speed: 250.0,
+            dampening: 0.95,
+        }
+    }
+}
+
+/// Landing faster than `min_speed` hurts. Every full `speed_step` of the landing speed is worth
+/// `damage_per_step`, minus the first `free_steps`.
+#[derive(serde::Deserialize, Debug, Clone, Copy)]
+pub struct FallDamage {
+    pub min_speed: f32,
+    pub speed_step: f32,
+    pub free_steps: i32,
+    pub damage_per_step: i32,
+}
+
+impl Default for FallDamage {
+    fn default() -> Self {
+        Self {
+            min_speed: 1500.0,
+            speed_step: 500.0,
+            free_steps: 2,
+            damage_per_step: 100 / 5,
+        }
+    }
+}
+
+impl FallDamage {
+    /// Landings can only hurt, never heal, and a zero `speed_step` can't divide by zero.
+    pub fn sanitized(self) -> Self {
+        Self {
+            min_speed: self.min_speed.max(0.0),
+            speed_step: self.speed_step.max(1.0),
+            free_steps: self.free_steps.max(0),
+            damage_per_step: self.damage_per_step.max(0),
+        }
+    }
+
+    /// Damage for landing with vertical velocity `velocity_y`, if the landing hurts at all
+    pub fn damage(&self, velocity_y: f32) -> Option<i32> {
+        let FallDamage {
+            min_speed,
+            speed_step,
+            free_steps,
+            damage_per_step,
+        } = self.sanitized();
+        if velocity_y >= -min_speed {
+            return None;
+        }
+
+        let steps = (velocity_y / speed_step).abs().floor() as i32 - free_steps;
+        (steps > 0 && damage_per_step > 0).then(|| damage_per_step.saturating_mul(steps))
+    }
+}
+
+/// Copies the asset into the resource once loading finishes and whenever the file changes.
+fn sync_ruleset(
+    data: Res<DataAssets>,
+    rulesets: Res<Assets<Ruleset>>,
+    mut ruleset: ResMut<Ruleset>,
+) {
+    if let Some(loaded) = rulesets.get(&data.ruleset) {
+        info!("Applying ruleset");
+        *ruleset = Ruleset {
+            physics: loaded.physics.sanitized(),
+            fall_damage: loaded.fall_damage.sanitized(),
+            ..loaded.clone()
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn landings_never_heal() {
+        let fall_damage = FallDamage {
+            min_speed: 100.0,
+            speed_step: 500.0,
+            free_steps: 5,
+            damage_per_step: 20,
+        };
+        for speed in [101.0, 600.0, 2400.0] {
+            assert_eq!(fall_damage.damage(-speed), None, "landing at {speed}");
+        }
+        assert_eq!(fall_damage.damage(-3000.0), Some(20));
+
+        let negative = FallDamage {
+            damage_per_step: -20,
+            ..FallDamage::default()
+        };
+        assert_eq!(negative.damage(-5000.0), None);
+    }
+
+    #[test]
+    fn zero_speed_step_still_gives_finite_damage() {
+        let fall_damage = FallDamage {
+            speed_step: 0.0,
+            ..FallDamage::default()
+        };
+        let damage = fall_damage.damage(-2000.0).unwrap();
+        assert!(damage > 0 && damage < i32::MAX);
+    }
+}
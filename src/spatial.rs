@@ -0,0 +1,129 @@
+use crate::app::{RESOLUTION_HEIGHT, RESOLUTION_WIDTH};
+use crate::game::{Apple, Obstacle, TimeExtender};
+use bevy::math::bounding::Aabb2d;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(SpatialGrid::default())
+        .add_observer(index_obstacle)
+        .add_observer(index_apple)
+        .add_observer(index_time_extender)
+        .add_observer(unindex_obstacle)
+        .add_observer(unindex_apple)
+        .add_observer(unindex_time_extender);
+}
+
+/// Broadphase for the collision systems. Colliders are bucketed into a uniform grid using the
+/// same 600x480 tiles as the world generator, so a query only has to look at the handful of
+/// cells around the dino instead of every obstacle spawned during the run.
+#[derive(Resource)]
+pub struct SpatialGrid {
+    cell_size: Vec2,
+    cells: HashMap<(i32, i32), Vec<Entity>>,
+    entries: HashMap<Entity, Vec<(i32, i32)>>,
+}
+
+impl Default for SpatialGrid {
+    fn default() -> Self {
+        Self {
+            cell_size: Vec2::new(RESOLUTION_WIDTH, RESOLUTION_HEIGHT),
+            cells: HashMap::default(),
+            entries: HashMap::default(),
+        }
+    }
+}
+
+impl SpatialGrid {
+    fn cell_range(&self, aabb: &Aabb2d) -> impl Iterator<Item = (i32, i32)> + use<> {
+        let min = (aabb.min / self.cell_size).floor().as_ivec2();
+        let max = (aabb.max / self.cell_size).floor().as_ivec2();
+        (min.x..=max.x).flat_map(move |i| (min.y..=max.y).map(move |j| (i, j)))
+    }
+
+    pub fn insert(&mut self, entity: Entity, aabb: &Aabb2d) {
+        self.remove(entity);
+
+        let cells = self.cell_range(aabb).collect::<Vec<_>>();
+        for cell in cells.iter() {
+            self.cells.entry(*cell).or_default().push(entity);
+        }
+        self.entries.insert(entity, cells);
+    }
+
+    pub fn remove(&mut self, entity: Entity) {
+        let Some(cells) = self.entries.remove(&entity) else {
+            return;
+        };
+
+        for cell in cells {
+            if let Some(bucket) = self.cells.get_mut(&cell) {
+                bucket.retain(|e| *e != entity);
+                if bucket.is_empty() {
+                    self.cells.remove(&cell);
+                }
+            }
+        }
+    }
+
+    /// Every entity whose cells overlap `aabb`. These are candidates only, callers still have
+    /// to run the narrow phase against the collider itself.
+    pub fn query(&self, aabb: &Aabb2d) -> Vec<Entity> {
+        let mut found = self
+            .cell_range(aabb)
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+            .collect::<Vec<_>>();
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.entries.clear();
+    }
+}
+
+fn index_obstacle(
+    trigger: Trigger<OnAdd, Obstacle>,
+    query: Query<&Obstacle>,
+    mut grid: ResMut<SpatialGrid>,
+) {
+    if let Ok(obstacle) = query.get(trigger.target()) {
+        grid.insert(trigger.target(), &obstacle.aabb);
+    }
+}
+
+fn index_apple(
+    trigger: Trigger<OnAdd, Apple>,
+    query: Query<&Apple>,
+    mut grid: ResMut<SpatialGrid>,
+) {
+    if let Ok(apple) = query.get(trigger.target()) {
+        grid.insert(trigger.target(), &apple.aabb);
+    }
+}
+
+fn index_time_extender(
+    trigger: Trigger<OnAdd, TimeExtender>,
+    query: Query<&TimeExtender>,
+    mut grid: ResMut<SpatialGrid>,
+) {
+    if let Ok(clock) = query.get(trigger.target()) {
+        grid.insert(trigger.target(), &clock.aabb);
+    }
+}
+
+fn unindex_obstacle(trigger: Trigger<OnRemove, Obstacle>, mut grid: ResMut<SpatialGrid>) {
+    grid.remove(trigger.target());
+}
+
+fn unindex_apple(trigger: Trigger<OnRemove, Apple>, mut grid: ResMut<SpatialGrid>) {
+    grid.remove(trigger.target());
+}
+
+fn unindex_time_extender(trigger: Trigger<OnRemove, TimeExtender>, mut grid: ResMut<SpatialGrid>) {
+    grid.remove(trigger.target());
+}
@@ -0,0 +1,164 @@
+use crate::app::AppState;
+use crate::input::{self, Action, VirtualButtons};
+use crate::util::handles::BODY_FONT;
+use bevy::picking::PickSet;
+use bevy::picking::pointer::PointerId;
+use bevy::prelude::*;
+use bevy_aspect_ratio_mask::Hud;
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(TouchControls::default())
+        .add_systems(Update, detect_touch)
+        .add_systems(
+            Update,
+            spawn_touch_controls.run_if(
+                in_state(AppState::Game)
+                    .and(resource_equals(TouchControls(true)))
+                    .and(resource_changed::<TouchControls>),
+            ),
+        )
+        .add_systems(OnEnter(AppState::Game), spawn_touch_controls)
+        .add_systems(
+            PreUpdate,
+            sync_virtual_buttons
+                .after(PickSet::Last)
+                .before(input::update_actions),
+        );
+}
+
+/// Whether the on-screen controls are shown. Off until the first touch, so desktop players
+/// never see them.
+#[derive(Resource, Default, Debug, PartialEq, Eq)]
+pub struct TouchControls(pub bool);
+
+fn detect_touch(touches: Res<Touches>, mut touch_controls: ResMut<TouchControls>) {
+    if !touch_controls.0 && touches.any_just_pressed() {
+        info!("Touch detected, showing touch controls");
+        touch_controls.0 = true;
+    }
+}
+
+/// An on-screen button that holds `action` for as long as any pointer is pressing it.
+/// Tracking every pointer lets a thumb walk while the other one jumps.
+#[derive(Component, Debug)]
+pub struct TouchButton {
+    pub action: Action,
+    pointers: Vec<PointerId>,
+}
+
+impl TouchButton {
+    fn new(action: Action) -> Self {
+        Self {
+            action,
+            pointers: Vec::new(),
+        }
+    }
+}
+
+#[derive(Component)]
+struct TouchControlsRoot;
+
+fn spawn_touch_controls(
+    mut commands: Commands,
+    hud: Res<Hud>,
+    touch_controls: Res<TouchControls>,
+    existing: Query<(), With<TouchControlsRoot>>,
+) {
+    if !touch_controls.0 || !existing.is_empty() {
+        return;
+    }
+
+    commands.entity(hud.0).with_children(|parent| {
+        parent
+            .spawn((
+                StateScoped(AppState::Game),
+                TouchControlsRoot,
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                Pickable::IGNORE,
+                ZIndex(10),
+            ))
+            .with_children(|p| {
+                // Clear of the volume toggles in the bottom left corner
+                touch_button(p, Action::Left, "<", Val::Px(50.0), Val::Auto, 90.0);
+                touch_button(p, Action::Right, ">", Val::Px(150.0), Val::Auto, 90.0);
+                touch_button(p, Action::Jump, "^", Val::Auto, Val::Px(15.0), 100.0);
+            });
+    });
+}
+
+fn touch_button(
+    parent: &mut ChildSpawnerCommands,
+    action: Action,
+    label: &str,
+    left: Val,
+    right: Val,
+    size: f32,
+) {
+    parent
+        .spawn((
+            TouchButton::new(action),
+            Node {
+                position_type: PositionType::Absolute,
+                left,
+                right,
+                bottom: Val::Px(15.0),
+                width: Val::Px(size),
+                height: Val::Px(size),
+                border: UiRect::all(Val::Px(3.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BorderColor(Color::srgba(1.0, 1.0, 1.0, 0.5)),
+            BorderRadius::all(Val::Px(16.0)),
+            BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.15)),
+            children![(
+                Text::new(label),
+                TextFont::from_font(BODY_FONT).with_font_size(40.0),
+                TextColor(Color::srgba(1.0, 1.0, 1.0, 0.7)),
+                // Only the button itself takes pointers, so sliding over the label isn't a
+                // release
+                Pickable::IGNORE,
+            )],
+        ))
+        .observe(press_touch_button)
+        .observe(release_touch_button::<Released>)
+        .observe(release_touch_button::<Out>)
+        .observe(release_touch_button::<Cancel>);
+}
+
+fn press_touch_button(trigger: Trigger<Pointer<Pressed>>, mut buttons: Query<&mut TouchButton>) {
+    let Ok(mut button) = buttons.get_mut(trigger.target()) else {
+        return;
+    };
+    if !button.pointers.contains(&trigger.pointer_id) {
+        button.pointers.push(trigger.pointer_id);
+    }
+}
+
+/// Lifting the finger or sliding it off the button both let go.
+fn release_touch_button<E: std::fmt::Debug + Clone + Reflect>(
+    trigger: Trigger<Pointer<E>>,
+    mut buttons: Query<&mut TouchButton>,
+) {
+    if let Ok(mut button) = buttons.get_mut(trigger.target()) {
+        button
+            .pointers
+            .retain(|pointer| *pointer != trigger.pointer_id);
+    }
+}
+
+fn sync_virtual_buttons(buttons: Query<&TouchButton>, mut virtual_buttons: ResMut<VirtualButtons>) {
+    virtual_buttons.0.clear();
+    virtual_buttons.0.extend(
+        buttons
+            .iter()
+            .filter(|button| !button.pointers.is_empty())
+            .map(|button| button.action),
+    );
+}
@@ -0,0 +1,770 @@
+use crate::app::{RESOLUTION_HEIGHT, RESOLUTION_WIDTH};
+use crate::ruleset::Physics;
+use bevy::math::Vec2;
+use bevy::math::bounding::{Aabb2d, BoundingVolume};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Compact record of everything generated for a tile. Collected apples and clocks are cleared
+/// from here so a tile that is streamed back in doesn't respawn them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GeneratedTile {
+    pub platforms: Vec<GeneratedObstacle>,
+    pub trees: Vec<GeneratedObstacle>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratedObstacle {
+    pub aabb: Aabb2d,
+    /// Clock on a platform or apple on a tree
+    pub collectible: Option<Aabb2d>,
+}
+
+/// Each tile gets its own rng derived from the run seed and its coordinates, so the layout of a
+/// tile doesn't depend on the order tiles are visited in.
+pub fn tile_rng(seed: u64, i: i32, j: i32) -> StdRng {
+    // splitmix64 finalizer, applied per input so neighbouring tiles get unrelated streams
+    fn mix(mut z: u64) -> u64 {
+        z = z.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    let hash = mix(mix(mix(seed) ^ i as u32 as u64) ^ j as u32 as u64);
+    StdRng::seed_from_u64(hash)
+}
+
+/// Turns whatever the player typed into a seed. Plain numbers are used as-is so seeds shown on
+/// the game over screen can be typed back in, anything else is hashed (FNV-1a).
+pub fn seed_from_text(text: &str) -> Option<u64> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+
+    if let Ok(seed) = text.parse::<u64>() {
+        return Some(seed);
+    }
+
+    let hash = text.bytes().fold(0xCBF2_9CE4_8422_2325_u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01B3)
+    });
+    Some(hash)
+}
+
+const MIN_TILE_ELEMENTS: usize = 4;
+const MAX_TILE_ELEMENTS: usize = 8;
+const MIN_PLATFORM_SPACING: f32 = 100.0;
+/// Staircases put every other step straight above each other, so spacing can't grow past that
+const MAX_PLATFORM_SPACING: f32 = 200.0;
+const MIN_TREE_SPACING: f32 = 200.0;
+const MAX_LANDING_ABOVE_TREE: f32 = 50.0;
+const MAX_LANDING_TREE_OFFSET: f32 = 150.0;
+const GENERATION_ATTEMPTS: usize = 16;
+const MIN_STAIR_STEP: f32 = 20.0;
+/// The dino never quite reaches full running speed mid-jump because of the dampening, so only
+/// count on this fraction of the ideal jump when checking reachability.
+const REACH_MARGIN: f32 = 0.8;
+
+/// Knobs that change between altitude bands and with difficulty, see `biome::Biome` and
+/// `difficulty::DifficultyCurve`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TileRules {
+    /// Chance that a new element is a tree rather than a platform
+    pub tree_chance: f64,
+    pub apple_chance: f64,
+    pub clock_chance: f64,
+    /// Minimum distance between platform centers
+    pub platform_spacing: f32,
+    pub platform_half_width: f32,
+    pub max_elements: usize,
+    /// Used to check that the dino can actually make every jump
+    pub physics: Physics,
+}
+
+impl Default for TileRules {
+    fn default() -> Self {
+        Self {
+            tree_chance: 0.5,
+            apple_chance: 0.5,
+            clock_chance: 1.0 / 16.0,
+            platform_spacing: MIN_PLATFORM_SPACING,
+            platform_half_width: 50.0,
+            max_elements: MAX_TILE_ELEMENTS,
+            physics: Physics::default(),
+        }
+    }
+}
+
+impl TileRules {
+    /// Keeps values coming from data files within what the generator can still make climbable.
+    pub fn sanitized(self) -> Self {
+        Self {
+            tree_chance: self.tree_chance.clamp(0.0, 1.0),
+            apple_chance: self.apple_chance.clamp(0.0, 1.0),
+            clock_chance: self.clock_chance.clamp(0.0, 1.0),
+            platform_spacing: self
+                .platform_spacing
+                .clamp(MIN_PLATFORM_SPACING, MAX_PLATFORM_SPACING),
+            platform_half_width: self.platform_half_width.clamp(20.0, 100.0),
+            max_elements: self
+                .max_elements
+                .clamp(MIN_TILE_ELEMENTS, MAX_TILE_ELEMENTS),
+            physics: self.physics.sanitized(),
+        }
+    }
+
+    fn platform_aabb(&self, center: Vec2) -> Aabb2d {
+        Aabb2d::new(center, Vec2::new(self.platform_half_width, 10.0))
+    }
+}
+
+/// Describes tile `(i, j)` of the world for `seed`. This is pure, so the same inputs always give
+/// the same tile no matter when or in which order tiles are generated.
+pub fn generate_tile(seed: u64, i: i32, j: i32, rules: &TileRules) -> GeneratedTile {
+    generate_tile_with(&mut tile_rng(seed, i, j), i, j, &rules.sanitized())
+}
+
+/// Generates a tile, rerolling it until it passes `validate_tile`. If no roll passes, the last
+/// one is patched with stepping platforms.
+fn generate_tile_with(rng: &mut impl Rng, i: i32, j: i32, rules: &TileRules) -> GeneratedTile {
+    for _ in 0..GENERATION_ATTEMPTS {
+        let tile = roll_tile(rng, i, j, rules);
+        if validate_tile(&tile, i, j, rules) {
+            return tile;
+        }
+    }
+
+    let mut tile = roll_tile(rng, i, j, rules);
+    patch_tile(&mut tile, rng, i, j, rules);
+    tile
+}
+
+fn roll_tile(rng: &mut impl Rng, i: i32, j: i32, rules: &TileRules) -> GeneratedTile {
+    // We're within a 600x480 box where we have to spawn obstacles (trees) and
+    // obstacles + platforms (landings) based on a set of rules and randomness.
+
+    // The obstacle-platform (landins) placement rules are:
+    // 1. They should be at least 100 pixels apart from each other in distance.
+    // 2. There should be at least:
+    //      a. one platform max 50 pixels above trees.
+    //      b. one platform max 400 pixels below trees at a max of 300 pixels away from either side of the tree.
+
+    // The obstacle (tree) placement rules are:
+    // 1. There should be a tree within a landing that's:
+    //    a. max 50 pixels above and 150 pixels away from either side of the landing
+    //    b. max 400 pixels below and 300 pixels away from either side of the landing.
+    // 2. Trees should be at least 200 pixels apart from each other.
+
+    // Rules 1 (platforms) and 2 (trees) are enforced as elements are placed, and every tree
+    // comes with its own landing for rule 2a. Whether the tile can actually be climbed is
+    // checked afterwards by `validate_tile`.
+
+    let mut tile = GeneratedTile::default();
+
+    // Let's loop within this tile to place landings and trees
+    // There should be a minimum of 4 elements per tile
+    // Placements can be rejected by the spacing rules, so give up eventually and let
+    // validation reroll the tile.
+    for _ in 0..64 {
+        let total_obstacles = tile.platforms.len() + tile.trees.len();
+        if total_obstacles >= MIN_TILE_ELEMENTS {
+            let roll = rng.random_range(0..2);
+            if roll == 0 {
+                break;
+            }
+        }
+
+        if total_obstacles >= rules.max_elements {
+            // Max 8 elements per tile
+            break;
+        }
+
+        if total_obstacles == 0 {
+            if !rng.random_bool(rules.tree_chance) {
+                // Start by placing a platform at a random position within the tile
+                let platform_x = (i as f32 * RESOLUTION_WIDTH)
+                    + rng.random_range(100.0..(RESOLUTION_WIDTH - 100.0));
+                let platform_y = (j as f32 * RESOLUTION_HEIGHT)
+                    + rng.random_range(
+                        -RESOLUTION_HEIGHT / 2.0 + 20.0..RESOLUTION_HEIGHT / 2.0 - 20.0,
+                    );
+
+                let clock = rng.random_bool(rules.clock_chance).then(|| {
+                    Aabb2d::new(
+                        Vec2::new(platform_x, platform_y + 20.0 / 2. + 8.0),
+                        Vec2::new(8.0, 8.0),
+                    )
+                });
+
+                tile.platforms.push(GeneratedObstacle {
+                    aabb: rules.platform_aabb(Vec2::new(platform_x, platform_y)),
+                    collectible: clock,
+                });
+            } else {
+                // Place a tree at a random position within the tile
+                let platform_x = (i as f32 * RESOLUTION_WIDTH)
+                    + rng.random_range(50.0..(RESOLUTION_WIDTH - 50.0));
+                let platform_y = (j as f32 * RESOLUTION_HEIGHT)
+                    + rng.random_range(
+                        -RESOLUTION_HEIGHT / 2.0 + 190.0..RESOLUTION_HEIGHT / 2.0 - 190.0,
+                    );
+
+                // Randomly add an apple tree
+                let apple = rng.random_bool(rules.apple_chance).then(|| {
+                    Aabb2d::new(
+                        Vec2::new(platform_x, platform_y + 380.0 / 2. + 8.0),
+                        Vec2::new(8.0, 8.0),
+                    )
+                });
+
+                try_place_tree(
+                    &mut tile,
+                    rng,
+                    rules,
+                    Vec2::new(platform_x, platform_y),
+                    apple,
+                );
+            }
+        }
+
+        if tile.platforms.is_empty() && tile.trees.is_empty() {
+            continue;
+        }
+
+        if !rng.random_bool(rules.tree_chance) {
+            // Add more elements relative to existing ones
+            // First try adding a platform relative to existing platforms
+            let mut added = None;
+            for existing_platform in &tile.platforms {
+                let roll = rng.random_range(0..50);
+                if roll == 0 {
+                    // Skip adding more platforms sometimes
+                    continue;
+                }
+                let platform_x_offset = rng.random_range(-150.0..150.0);
+                let platform_y_offset = rng.random_range(-400.0..400.0);
+                let platform_x = existing_platform.aabb.min.x + platform_x_offset;
+                let platform_y = existing_platform.aabb.min.y + platform_y_offset;
+                added = Some(GeneratedObstacle {
+                    aabb: rules.platform_aabb(Vec2::new(platform_x, platform_y)),
+                    collectible: None,
+                });
+                break;
+            }
+            if let Some(platform) = added
+                && platform_fits(&tile, rules, &platform.aabb)
+            {
+                tile.platforms.push(platform);
+            }
+
+            // Next try adding a platform relative to existing non-platform obstacles (trees)
+
+            continue;
+        } else {
+            // Add tree relative to existing obstacles
+            let mut added = None;
+            for existing_obstacle in &tile.trees {
+                let roll = rng.random_range(0..50);
+                if roll == 0 {
+                    // Skip adding more platforms sometimes
+                    continue;
+                }
+                let obstacle_x_offset = rng.random_range(-300.0..300.0);
+                let obstacle_y_offset = rng.random_range(-400.0..150.0);
+                let obstacle_x = existing_obstacle.aabb.min.x + obstacle_x_offset;
+                let obstacle_y = existing_obstacle.aabb.min.y + obstacle_y_offset;
+                added = Some(Vec2::new(obstacle_x, obstacle_y));
+                break;
+            }
+            if let Some(position) = added {
+                try_place_tree(&mut tile, rng, rules, position, None);
+            }
+        }
+    }
+
+    tile
+}
+
+pub fn tree_aabb(center: Vec2) -> Aabb2d {
+    Aabb2d::new(center, Vec2::new(25., 190.0))
+}
+
+fn platform_fits(tile: &GeneratedTile, rules: &TileRules, aabb: &Aabb2d) -> bool {
+    tile.platforms
+        .iter()
+        .all(|p| p.aabb.center().distance(aabb.center()) >= rules.platform_spacing)
+}
+
+fn tree_fits(tile: &GeneratedTile, aabb: &Aabb2d) -> bool {
+    tile.trees
+        .iter()
+        .all(|t| t.aabb.center().distance(aabb.center()) >= MIN_TREE_SPACING)
+}
+
+/// Places a tree together with the landing a climber can step onto from its top. The tree is
+/// skipped if either of them breaks the spacing rules or the tile has no room for both.
+fn try_place_tree(
+    tile: &mut GeneratedTile,
+    rng: &mut impl Rng,
+    rules: &TileRules,
+    position: Vec2,
+    apple: Option<Aabb2d>,
+) -> bool {
+    if tile.platforms.len() + tile.trees.len() + 2 > rules.max_elements {
+        return false;
+    }
+
+    let tree = tree_aabb(position);
+    let landing_top = tree.max.y + rng.random_range(10.0..MAX_LANDING_ABOVE_TREE);
+    let landing = rules.platform_aabb(Vec2::new(
+        position.x + rng.random_range(-MAX_LANDING_TREE_OFFSET..MAX_LANDING_TREE_OFFSET),
+        landing_top - 10.0,
+    ));
+
+    if !tree_fits(tile, &tree) || !platform_fits(tile, rules, &landing) {
+        return false;
+    }
+
+    tile.trees.push(GeneratedObstacle {
+        aabb: tree,
+        collectible: apple,
+    });
+    tile.platforms.push(GeneratedObstacle {
+        aabb: landing,
+        collectible: None,
+    });
+    true
+}
+
+/// Checks the density and spacing rules and that the tile can be climbed from bottom to top.
+pub fn validate_tile(tile: &GeneratedTile, i: i32, j: i32, rules: &TileRules) -> bool {
+    let total = tile.platforms.len() + tile.trees.len();
+    if !(MIN_TILE_ELEMENTS..=rules.max_elements).contains(&total) {
+        return false;
+    }
+
+    for (index, platform) in tile.platforms.iter().enumerate() {
+        let too_close = tile.platforms[index + 1..].iter().any(|other| {
+            other.aabb.center().distance(platform.aabb.center()) < rules.platform_spacing
+        });
+        if too_close {
+            return false;
+        }
+    }
+
+    for (index, tree) in tile.trees.iter().enumerate() {
+        let too_close = tile.trees[index + 1..]
+            .iter()
+            .any(|other| other.aabb.center().distance(tree.aabb.center()) < MIN_TREE_SPACING);
+        if too_close {
+            return false;
+        }
+
+        let has_landing = tile.platforms.iter().any(|platform| {
+            let above = platform.aabb.max.y - tree.aabb.max.y;
+            let offset = (platform.aabb.center().x - tree.aabb.center().x).abs();
+            above > 0.0 && above <= MAX_LANDING_ABOVE_TREE && offset <= MAX_LANDING_TREE_OFFSET
+        });
+        if !has_landing {
+            return false;
+        }
+    }
+
+    climb(tile, i, j, &rules.physics).0
+}
+
+/// Something the dino can get onto. Platforms can only be landed on from above, so for them
+/// `bottom == top`. Trunks can be grabbed anywhere along their height by attacking into them.
+#[derive(Debug, Clone, Copy)]
+struct Ledge {
+    bottom: f32,
+    top: f32,
+    left: f32,
+    right: f32,
+}
+
+impl Ledge {
+    fn platform(aabb: &Aabb2d) -> Self {
+        Self {
+            bottom: aabb.max.y,
+            top: aabb.max.y,
+            left: aabb.min.x,
+            right: aabb.max.x,
+        }
+    }
+
+    fn tree(aabb: &Aabb2d) -> Self {
+        Self {
+            bottom: aabb.min.y,
+            top: aabb.max.y,
+            left: aabb.min.x,
+            right: aabb.max.x,
+        }
+    }
+
+    /// Whether the dino standing on top of `self` can get onto `other` in one jump
+    fn reaches(&self, other: &Ledge, physics: &Physics) -> bool {
+        let rise = if self.top < other.bottom {
+            other.bottom - self.top
+        } else if self.top > other.top {
+            other.top - self.top
+        } else {
+            0.0
+        };
+
+        let gap = (other.left - self.right)
+            .max(self.left - other.right)
+            .max(0.0);
+        jump_reach(physics, rise).is_some_and(|reach| gap <= reach * REACH_MARGIN)
+    }
+}
+
+fn jump_velocity(physics: &Physics) -> f32 {
+    (2.0 * physics.jump_height * physics.gravity.abs()).sqrt() * physics.max_jump_time
+}
+
+/// Highest point of a full jump above the takeoff height
+fn max_jump_rise(physics: &Physics) -> f32 {
+    let v = jump_velocity(physics);
+    v * physics.max_jump_time + v * v / (2.0 * physics.gravity.abs())
+}
+
+/// Horizontal distance covered by a full, held jump by the time the dino comes back down to
+/// `rise` above the takeoff height. `None` if the jump never gets that high.
+fn jump_reach(physics: &Physics, rise: f32) -> Option<f32> {
+    if rise > max_jump_rise(physics) {
+        return None;
+    }
+
+    // While space is held the dino rises at a constant speed, then it's plain projectile motion
+    let v = jump_velocity(physics);
+    let g = physics.gravity.abs();
+    let held_rise = v * physics.max_jump_time;
+    let falling_time = (v + (v * v + 2.0 * g * (held_rise - rise)).sqrt()) / g;
+    Some(physics.running_speed * (physics.max_jump_time + falling_time))
+}
+
+/// Rise between stepping platforms that a full jump comfortably makes. Sanitized physics keep
+/// it well above the floor, which only makes sure the staircase loop always ends.
+fn stair_step(physics: &Physics) -> f32 {
+    (max_jump_rise(physics) * REACH_MARGIN * 0.85).max(MIN_STAIR_STEP)
+}
+
+/// The tile whose `tile_bounds` hold `position`. Rows are centred on multiples of the tile
+/// height while columns start at multiples of its width.
+pub fn tile_at(position: Vec2) -> (i32, i32) {
+    (
+        (position.x / RESOLUTION_WIDTH).floor() as i32,
+        (position.y / RESOLUTION_HEIGHT + 0.5).floor() as i32,
+    )
+}
+
+pub fn tile_bounds(i: i32, j: i32) -> (Vec2, Vec2) {
+    let min = Vec2::new(
+        i as f32 * RESOLUTION_WIDTH,
+        j as f32 * RESOLUTION_HEIGHT - RESOLUTION_HEIGHT / 2.0,
+    );
+    (min, min + Vec2::new(RESOLUTION_WIDTH, RESOLUTION_HEIGHT))
+}
+
+/// Walks every ledge reachable from the bottom edge of the tile. Returns whether the top edge
+/// can be reached and the ledges that were visited.
+fn climb(tile: &GeneratedTile, i: i32, j: i32, physics: &Physics) -> (bool, Vec<Ledge>) {
+    let (min, max) = tile_bounds(i, j);
+    let ledges = tile
+        .platforms
+        .iter()
+        .map(|p| Ledge::platform(&p.aabb))
+        .chain(tile.trees.iter().map(|t| Ledge::tree(&t.aabb)))
+        .collect::<Vec<_>>();
+
+    // Coming up from the tile below, the dino could be anywhere along the bottom edge
+    let entry = Ledge {
+        bottom: min.y,
+        top: min.y,
+        left: min.x,
+        right: max.x,
+    };
+    let exit_height = max.y - max_jump_rise(physics) * REACH_MARGIN;
+
+    let mut visited = vec![false; ledges.len()];
+    let mut reached = vec![];
+    let mut frontier = vec![entry];
+    while let Some(current) = frontier.pop() {
+        if current.top >= exit_height {
+            return (true, reached);
+        }
+
+        for (index, ledge) in ledges.iter().enumerate() {
+            if !visited[index] && current.reaches(ledge, physics) {
+                visited[index] = true;
+                reached.push(*ledge);
+                frontier.push(*ledge);
+            }
+        }
+    }
+
+    (false, reached)
+}
+
+/// Adds stepping platforms above the highest reachable ledge until the tile can be climbed.
+/// If that isn't possible within the density rule, the tile is replaced by a plain staircase.
+fn patch_tile(tile: &mut GeneratedTile, rng: &mut impl Rng, i: i32, j: i32, rules: &TileRules) {
+    let (min, max) = tile_bounds(i, j);
+    let step = stair_step(&rules.physics);
+
+    while tile.platforms.len() + tile.trees.len() < rules.max_elements {
+        let (climbable, reached) = climb(tile, i, j, &rules.physics);
+        if climbable {
+            break;
+        }
+
+        let from = reached
+            .into_iter()
+            .max_by(|a, b| a.top.total_cmp(&b.top))
+            .unwrap_or(Ledge {
+                bottom: min.y,
+                top: min.y,
+                left: min.x,
+                right: max.x,
+            });
+
+        let x = ((from.left + from.right) / 2.0 + rng.random_range(-120.0..120.0))
+            .clamp(min.x + 50.0, max.x - 50.0);
+        let platform = rules.platform_aabb(Vec2::new(x, from.top + step - 10.0));
+        if !platform_fits(tile, rules, &platform) {
+            break;
+        }
+        tile.platforms.push(GeneratedObstacle {
+            aabb: platform,
+            collectible: None,
+        });
+    }
+
+    if !validate_tile(tile, i, j, rules) {
+        *tile = staircase_tile(rng, i, j, rules);
+    }
+}
+
+/// Last resort layout that always passes `validate_tile`
+fn staircase_tile(rng: &mut impl Rng, i: i32, j: i32, rules: &TileRules) -> GeneratedTile {
+    let (min, max) = tile_bounds(i, j);
+    let step = stair_step(&rules.physics);
+    let center_x = (min.x + max.x) / 2.0 + rng.random_range(-100.0..100.0);
+    // Far enough apart sideways that consecutive steps respect the platform spacing
+    let offset = ((rules.platform_spacing.powi(2) - step.powi(2))
+        .max(0.0)
+        .sqrt()
+        / 2.0
+        + 1.0)
+        .max(60.0);
+
+    let mut tile = GeneratedTile::default();
+    let mut top = min.y + step;
+    let mut side = if rng.random_bool(0.5) { 1.0 } else { -1.0 };
+    while tile.platforms.len() < MIN_TILE_ELEMENTS || top < max.y - step {
+        tile.platforms.push(GeneratedObstacle {
+            aabb: rules.platform_aabb(Vec2::new(center_x + side * offset, top - 10.0)),
+            collectible: None,
+        });
+        top += step;
+        side = -side;
+    }
+    tile
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_tiles() -> impl Iterator<Item = (i32, i32, GeneratedTile)> {
+        (0..10_u64).flat_map(|seed| {
+            (-3..3).flat_map(move |i| {
+                (-3..3).map(move |j| (i, j, generate_tile(seed, i, j, &TileRules::default())))
+            })
+        })
+    }
+
+    #[test]
+    fn tiles_have_four_to_eight_elements() {
+        for (i, j, tile) in sample_tiles() {
+            let total = tile.platforms.len() + tile.trees.len();
+            assert!(
+                (MIN_TILE_ELEMENTS..=MAX_TILE_ELEMENTS).contains(&total),
+                "tile ({i}, {j}) has {total} elements"
+            );
+        }
+    }
+
+    #[test]
+    fn platforms_are_at_least_100px_apart() {
+        for (i, j, tile) in sample_tiles() {
+            for (index, a) in tile.platforms.iter().enumerate() {
+                for b in &tile.platforms[index + 1..] {
+                    let distance = a.aabb.center().distance(b.aabb.center());
+                    assert!(
+                        distance >= 100.0,
+                        "tile ({i}, {j}) platforms {distance}px apart"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn trees_are_at_least_200px_apart() {
+        for (i, j, tile) in sample_tiles() {
+            for (index, a) in tile.trees.iter().enumerate() {
+                for b in &tile.trees[index + 1..] {
+                    let distance = a.aabb.center().distance(b.aabb.center());
+                    assert!(
+                        distance >= 200.0,
+                        "tile ({i}, {j}) trees {distance}px apart"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn every_tree_has_a_landing_within_50px_above() {
+        for (i, j, tile) in sample_tiles() {
+            for tree in &tile.trees {
+                let has_landing = tile.platforms.iter().any(|platform| {
+                    let above = platform.aabb.max.y - tree.aabb.max.y;
+                    above > 0.0 && above <= 50.0
+                });
+                assert!(has_landing, "tile ({i}, {j}) has a tree without a landing");
+            }
+        }
+    }
+
+    #[test]
+    fn tiles_can_be_climbed() {
+        for (i, j, tile) in sample_tiles() {
+            assert!(
+                climb(&tile, i, j, &Physics::default()).0,
+                "tile ({i}, {j}) can't be climbed"
+            );
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_same_tile_in_any_order() {
+        let rules = TileRules::default();
+        let forward = (0..5)
+            .map(|i| generate_tile(42, i, 3, &rules))
+            .collect::<Vec<_>>();
+        let backward = (0..5)
+            .rev()
+            .map(|i| generate_tile(42, i, 3, &rules))
+            .collect::<Vec<_>>();
+        assert_eq!(forward, backward.into_iter().rev().collect::<Vec<_>>());
+        assert_ne!(
+            generate_tile(42, 0, 0, &rules),
+            generate_tile(43, 0, 0, &rules)
+        );
+    }
+
+    #[test]
+    fn tree_chance_controls_the_obstacle_mix() {
+        let rules = TileRules {
+            tree_chance: 0.0,
+            apple_chance: 1.0,
+            clock_chance: 0.0,
+            ..TileRules::default()
+        };
+        for seed in 0..10 {
+            let tile = generate_tile(seed, 0, 5, &rules);
+            assert!(tile.trees.is_empty());
+            assert!(tile.platforms.iter().all(|p| p.collectible.is_none()));
+        }
+    }
+
+    #[test]
+    fn hardest_rules_still_give_valid_tiles() {
+        let rules = TileRules {
+            platform_spacing: MAX_PLATFORM_SPACING,
+            platform_half_width: 20.0,
+            max_elements: 6,
+            ..TileRules::default()
+        };
+        for seed in 0..10 {
+            for j in 0..5 {
+                let tile = generate_tile(seed, 0, j, &rules);
+                assert!(
+                    validate_tile(&tile, 0, j, &rules),
+                    "tile (0, {j}) is invalid"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn degenerate_physics_still_finish_generating() {
+        let degenerate = [
+            Physics {
+                gravity: 0.0,
+                jump_height: 0.0,
+                max_jump_time: 0.0,
+                running_speed: 0.0,
+                dampening: 0.0,
+            },
+            Physics {
+                gravity: 1e-6,
+                jump_height: -1500.0,
+                max_jump_time: f32::NAN,
+                running_speed: -250.0,
+                dampening: 2.0,
+            },
+        ];
+        for physics in degenerate {
+            let rules = TileRules {
+                physics,
+                ..TileRules::default()
+            }
+            .sanitized();
+            for j in 0..3 {
+                // Used to loop forever building the staircase
+                let tile = generate_tile(7, 0, j, &rules);
+                assert!(tile.platforms.len() + tile.trees.len() >= MIN_TILE_ELEMENTS);
+                let staircase = staircase_tile(&mut tile_rng(7, 0, j), 0, j, &rules);
+                assert!(staircase.platforms.len() <= MAX_TILE_ELEMENTS * 4);
+            }
+        }
+    }
+
+    #[test]
+    fn tile_at_matches_tile_bounds() {
+        for i in -2..2 {
+            for j in -2..2 {
+                let (min, max) = tile_bounds(i, j);
+                for position in [min, (min + max) / 2.0, max - Vec2::splat(0.01)] {
+                    assert_eq!(
+                        tile_at(position),
+                        (i, j),
+                        "{position} is outside ({i}, {j})"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn staircase_passes_validation() {
+        let hardest = TileRules {
+            platform_spacing: MAX_PLATFORM_SPACING,
+            platform_half_width: 20.0,
+            max_elements: MIN_TILE_ELEMENTS,
+            ..TileRules::default()
+        };
+        for rules in [TileRules::default(), hardest] {
+            for seed in 0..20 {
+                let tile = staircase_tile(&mut tile_rng(seed, 1, -2), 1, -2, &rules);
+                assert!(validate_tile(&tile, 1, -2, &rules));
+            }
+        }
+    }
+}